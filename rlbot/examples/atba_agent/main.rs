@@ -1,12 +1,13 @@
-use std::{f32::consts::PI, sync::Arc};
+use std::sync::Arc;
 
 use rlbot::{
     RLBotConnection,
-    agents::{BotAgent, run_bot_agents},
+    agents::{BotAgent, TickContext, run_bot_agents},
     flat::{
         ControllableInfo, ControllerState, FieldInfo, GamePacket, MatchConfiguration, PlayerClass,
         PlayerInput,
     },
+    player::PlayerInfoExt,
     util::{AgentEnvironment, PacketQueue},
 };
 
@@ -52,7 +53,12 @@ impl BotAgent for AtbaAgent {
         }
     }
 
-    fn tick(&mut self, game_packet: &GamePacket, packet_queue: &mut PacketQueue) {
+    fn tick(
+        &mut self,
+        game_packet: &GamePacket,
+        _context: TickContext,
+        packet_queue: &mut PacketQueue,
+    ) {
         let Some(ball) = game_packet.balls.first() else {
             // If theres no ball, theres nothing to chase, don't do anything
             return;
@@ -63,17 +69,8 @@ impl BotAgent for AtbaAgent {
             return;
         }
 
-        let target = &ball.physics;
-        let car = game_packet.players[self.index as usize].physics;
-
-        let bot_to_target_angle = f32::atan2(
-            target.location.y - car.location.y,
-            target.location.x - car.location.x,
-        );
-
-        let mut bot_front_to_target_angle = bot_to_target_angle - car.rotation.yaw;
-
-        bot_front_to_target_angle = (bot_front_to_target_angle + PI).rem_euclid(2. * PI) - PI;
+        let car = &game_packet.players[self.index as usize];
+        let bot_front_to_target_angle = car.angle_to(ball.physics.location.into());
 
         let mut controller = ControllerState::default();
 
@@ -111,7 +108,7 @@ fn main() {
     // all of the bots in a team.
 
     // Blocking.
-    run_bot_agents::<AtbaAgent>(agent_id.clone(), true, true, rlbot_connection)
+    run_bot_agents::<AtbaAgent>(agent_id.clone(), true, true, None, rlbot_connection)
         .expect("run_bot_agents crashed");
 
     println!("Agent(s) with agent_id `{agent_id}` exited nicely");
@@ -1,12 +1,11 @@
-use std::f32::consts::PI;
-
 use rlbot::{
     RLBotConnection,
-    agents::{HivemindAgent, run_hivemind_agent},
+    agents::{HivemindAgent, TickContext, run_hivemind_agent},
     flat::{
         ControllableTeamInfo, ControllerState, FieldInfo, GamePacket, MatchConfiguration,
         PlayerClass, PlayerInput,
     },
+    player::PlayerInfoExt,
     util::{AgentEnvironment, PacketQueue},
 };
 
@@ -61,7 +60,12 @@ impl HivemindAgent for AtbaHivemind {
         }
     }
 
-    fn tick(&mut self, game_packet: GamePacket, packet_queue: &mut PacketQueue) {
+    fn tick(
+        &mut self,
+        game_packet: GamePacket,
+        _context: TickContext,
+        packet_queue: &mut PacketQueue,
+    ) {
         let Some(ball) = game_packet.balls.first() else {
             // If theres no ball, theres nothing to chase, don't do anything
             return;
@@ -73,17 +77,8 @@ impl HivemindAgent for AtbaHivemind {
         }
 
         for &index in &self.indices {
-            let target = &ball.physics;
-            let car = game_packet.players[index as usize].physics;
-
-            let bot_to_target_angle = f32::atan2(
-                target.location.y - car.location.y,
-                target.location.x - car.location.x,
-            );
-
-            let mut bot_front_to_target_angle = bot_to_target_angle - car.rotation.yaw;
-
-            bot_front_to_target_angle = (bot_front_to_target_angle + PI).rem_euclid(2. * PI) - PI;
+            let car = &game_packet.players[index as usize];
+            let bot_front_to_target_angle = car.angle_to(ball.physics.location.into());
 
             let mut controller = ControllerState::default();
 
@@ -122,7 +117,7 @@ fn main() {
     // all of the bots in a team.
 
     // Blocking.
-    run_hivemind_agent::<AtbaHivemind>(agent_id.clone(), true, true, rlbot_connection)
+    run_hivemind_agent::<AtbaHivemind>(agent_id.clone(), true, true, None, rlbot_connection)
         .expect("run_hivemind_agent crashed");
 
     println!("Hivemind with agent_id `{agent_id}` exited nicely");
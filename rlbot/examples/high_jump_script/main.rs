@@ -1,6 +1,6 @@
 use rlbot::{
     RLBotConnection,
-    agents::{ScriptAgent, run_script_agent},
+    agents::{ScriptAgent, TickContext, run_script_agent},
     flat::{
         DesiredCarState, DesiredGameState, DesiredPhysics, FieldInfo, GamePacket,
         MatchConfiguration, MatchPhase, Vector3Partial,
@@ -41,7 +41,12 @@ impl ScriptAgent for MyScript {
         }
     }
 
-    fn tick(&mut self, game_packet: GamePacket, packet_queue: &mut PacketQueue) {
+    fn tick(
+        &mut self,
+        game_packet: GamePacket,
+        _context: TickContext,
+        packet_queue: &mut PacketQueue,
+    ) {
         if game_packet.match_info.match_phase != MatchPhase::Active {
             return;
         }
@@ -89,7 +94,7 @@ fn main() {
     let rlbot_connection = RLBotConnection::new(&server_addr).expect("connection");
 
     // Blocking.
-    run_script_agent::<MyScript>(agent_id.clone(), true, true, rlbot_connection)
+    run_script_agent::<MyScript>(agent_id.clone(), true, true, None, rlbot_connection)
         .expect("run_script_agent crashed");
 
     println!("Script with agent_id `{agent_id}` exited nicely");
@@ -0,0 +1,140 @@
+//! Records fixtures for the tests in `rlbot/tests` from a running match.
+//!
+//! `cargo run --example record_fixture -- ball rlbot/tests/fixtures/ball_roll.json`
+//! saves the next ball prediction sent by core. Put the ball in the state to
+//! record, e.g. with state setting, right before running it.
+//!
+//! `cargo run --example record_fixture -- car rlbot/tests/fixtures/car_drive_boost_jump.json 0 1.5`
+//! saves the packets of the car at index 0, with its inputs, for 1.5 seconds.
+
+use std::{env, fs};
+
+use rlbot::{
+    RLBotConnection,
+    flat::{ConnectionSettings, CoreMessage, Rotator, Vector3},
+    util::AgentEnvironment,
+};
+use serde_json::{Value, json};
+
+fn vector(vector: Vector3) -> Value {
+    json!([vector.x, vector.y, vector.z])
+}
+
+fn rotator(rotator: Rotator) -> Value {
+    json!([rotator.pitch, rotator.yaw, rotator.roll])
+}
+
+fn record_car(connection: &mut RLBotConnection, index: usize, duration: f32) -> Value {
+    let mut packets = Vec::new();
+    let mut start = None;
+
+    loop {
+        let CoreMessage::GamePacket(game_packet) = connection.recv_packet().unwrap() else {
+            continue;
+        };
+        let Some(player) = game_packet.players.get(index) else {
+            continue;
+        };
+
+        let seconds = game_packet.match_info.seconds_elapsed;
+        let start = *start.get_or_insert(seconds);
+        if seconds - start > duration {
+            break;
+        }
+
+        let physics = &player.physics;
+        let input = &player.last_input;
+        packets.push(json!({
+            "seconds": seconds,
+            "location": vector(physics.location),
+            "rotation": rotator(physics.rotation),
+            "velocity": vector(physics.velocity),
+            "angular_velocity": vector(physics.angular_velocity),
+            "boost": player.boost,
+            "air_state": format!("{:?}", player.air_state),
+            "has_jumped": player.has_jumped,
+            "input": {
+                "throttle": input.throttle,
+                "steer": input.steer,
+                "pitch": input.pitch,
+                "yaw": input.yaw,
+                "roll": input.roll,
+                "boost": input.boost,
+                "jump": input.jump,
+                "handbrake": input.handbrake,
+            },
+        }));
+    }
+
+    json!({
+        "source": "GamePackets recorded from core",
+        "packets": packets,
+    })
+}
+
+fn record_ball(connection: &mut RLBotConnection) -> Value {
+    loop {
+        let CoreMessage::BallPrediction(ball_prediction) = connection.recv_packet().unwrap() else {
+            continue;
+        };
+        let Some(first) = ball_prediction.slices.first() else {
+            continue;
+        };
+
+        return json!({
+            "source": "BallPrediction recorded from core",
+            "game_seconds": first.game_seconds,
+            "location": vector(first.physics.location),
+            "velocity": vector(first.physics.velocity),
+            "angular_velocity": vector(first.physics.angular_velocity),
+            "slices": ball_prediction
+                .slices
+                .iter()
+                .map(|slice| {
+                    let location = slice.physics.location;
+                    json!([slice.game_seconds, location.x, location.y, location.z])
+                })
+                .collect::<Vec<_>>(),
+        });
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let (kind, path, rest) = match args.as_slice() {
+        [kind, path, rest @ ..] => (kind.as_str(), path, rest),
+        _ => panic!("usage: record_fixture ball <path> | car <path> <index> <seconds>"),
+    };
+
+    let AgentEnvironment {
+        server_addr,
+        agent_id,
+    } = AgentEnvironment::from_env();
+    let agent_id = agent_id.unwrap_or_else(|| "rlbot/rust-fixture-recorder".into());
+
+    let mut rlbot_connection = RLBotConnection::new(&server_addr).expect("connection");
+
+    println!("Connected");
+
+    rlbot_connection
+        .send_packet(ConnectionSettings {
+            wants_ball_predictions: true,
+            wants_comms: false,
+            close_between_matches: true,
+            agent_id,
+        })
+        .unwrap();
+
+    let fixture = match (kind, rest) {
+        ("ball", []) => record_ball(&mut rlbot_connection),
+        ("car", [index, seconds]) => record_car(
+            &mut rlbot_connection,
+            index.parse().expect("car index"),
+            seconds.parse().expect("seconds to record"),
+        ),
+        _ => panic!("unknown fixture kind {kind} or wrong arguments"),
+    };
+
+    fs::write(path, serde_json::to_string(&fixture).unwrap()).unwrap();
+    println!("Saved {path}");
+}
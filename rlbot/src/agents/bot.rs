@@ -2,9 +2,15 @@ use std::{io::ErrorKind, sync::Arc, thread};
 
 use mio::Interest;
 
-use crate::{RLBotConnection, RLBotError, StartingInfo, flat::*, pkanal, util::PacketQueue};
+use crate::{
+    RLBotConnection, RLBotError, StartingInfo,
+    flat::*,
+    latency::{self, LatencyMonitor},
+    pkanal,
+    util::PacketQueue,
+};
 
-use super::AgentError;
+use super::{AgentError, FrameTracker, TickContext};
 
 #[allow(unused_variables)]
 pub trait BotAgent {
@@ -16,7 +22,12 @@ pub trait BotAgent {
         field_info: Arc<FieldInfo>,
         packet_queue: &mut PacketQueue,
     ) -> Self;
-    fn tick(&mut self, game_packet: &GamePacket, packet_queue: &mut PacketQueue);
+    fn tick(
+        &mut self,
+        game_packet: &GamePacket,
+        context: TickContext,
+        packet_queue: &mut PacketQueue,
+    );
     fn on_match_comm(&mut self, match_comm: &MatchComm, packet_queue: &mut PacketQueue) {}
     fn on_ball_prediction(
         &mut self,
@@ -36,6 +47,10 @@ pub trait BotAgent {
 /// Run multiple agents with n agents per thread. They share a connection.
 /// Ok(()) means a successful exit; one of the bots received a None packet.
 ///
+/// If `latency_monitor` is set, every agent thread measures the latency to
+/// core with a clone of it and passes the stats to every tick in
+/// [TickContext::latency]. Its responses never reach the agent.
+///
 /// # Errors
 ///
 /// Returns an error if an agent panics or if there is an error with the connection.
@@ -48,6 +63,7 @@ pub fn run_bot_agents<T: BotAgent>(
     agent_id: String,
     wants_ball_predictions: bool,
     wants_comms: bool,
+    latency_monitor: Option<LatencyMonitor>,
     mut connection: RLBotConnection,
 ) -> Result<(), AgentError> {
     connection.send_packet(ConnectionSettings {
@@ -102,6 +118,7 @@ pub fn run_bot_agents<T: BotAgent>(
         let (incoming_sender, incoming_recver) = kanal::unbounded::<Arc<CoreMessage>>();
         let match_configuration = match_configuration.clone();
         let field_info = field_info.clone();
+        let latency_monitor = latency_monitor.clone();
 
         let outgoing_sender = outgoing_sender.clone();
 
@@ -119,6 +136,7 @@ pub fn run_bot_agents<T: BotAgent>(
                         controllable_info,
                         match_configuration,
                         field_info,
+                        latency_monitor,
                         outgoing_sender,
                     );
                 })
@@ -188,6 +206,7 @@ fn run_bot_agent<T: BotAgent>(
     controllable_info: ControllableInfo,
     match_configuration: Arc<MatchConfiguration>,
     field_info: Arc<FieldInfo>,
+    latency_monitor: Option<LatencyMonitor>,
     outgoing_sender: pkanal::Sender<Vec<InterfaceMessage>>,
 ) {
     let mut outgoing_queue = PacketQueue::default();
@@ -203,6 +222,8 @@ fn run_bot_agent<T: BotAgent>(
         .send(outgoing_queue.empty())
         .expect("Couldn't send outgoing");
 
+    let mut frame_tracker = FrameTracker::new();
+    let mut latency = latency_monitor.map(LatencyMonitor::for_runner);
     loop {
         let Ok(packet) = incoming_recver.recv() else {
             panic!("channel recv failed")
@@ -211,7 +232,15 @@ fn run_bot_agent<T: BotAgent>(
         match &*packet {
             CoreMessage::DisconnectSignal(_) => break,
             CoreMessage::GamePacket(x) => {
-                agent.tick(x, &mut outgoing_queue);
+                outgoing_queue.observe_game_packet(x);
+                if let Some(latency) = &mut latency {
+                    latency.tick(&mut outgoing_queue);
+                }
+                let context = TickContext {
+                    latency: latency.as_ref().and_then(LatencyMonitor::stats),
+                    ..frame_tracker.update(x)
+                };
+                agent.tick(x, context, &mut outgoing_queue);
             }
             CoreMessage::MatchComm(x) => {
                 agent.on_match_comm(x, &mut outgoing_queue);
@@ -223,7 +252,14 @@ fn run_bot_agent<T: BotAgent>(
                 agent.on_rendering_status(x, &mut outgoing_queue);
             }
             CoreMessage::PingResponse(x) => {
-                agent.on_ping_response(x, &mut outgoing_queue);
+                // Responses to the pings of any runner aren't forwarded
+                if latency::is_runner_cookie(x.cookie) {
+                    if let Some(latency) = &mut latency {
+                        latency.on_ping_response(x);
+                    }
+                } else {
+                    agent.on_ping_response(x, &mut outgoing_queue);
+                }
             }
             CoreMessage::PingRequest(x) => {
                 outgoing_queue.push(PingResponse { cookie: x.cookie });
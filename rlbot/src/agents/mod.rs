@@ -1,11 +1,13 @@
 mod bot;
 mod hivemind;
 mod script;
+mod tick_context;
 
 pub use {
     bot::{BotAgent, run_bot_agents},
     hivemind::{HivemindAgent, run_hivemind_agent},
     script::{ScriptAgent, run_script_agent},
+    tick_context::{FrameTracker, TickContext},
 };
 
 #[derive(thiserror::Error, Debug)]
@@ -3,9 +3,13 @@ use rlbot_flat::flat::{
     MatchComm, MatchConfiguration, PingResponse, RenderingStatus,
 };
 
-use crate::{RLBotConnection, StartingInfo, util::PacketQueue};
+use crate::{
+    RLBotConnection, StartingInfo,
+    latency::{self, LatencyMonitor},
+    util::PacketQueue,
+};
 
-use super::AgentError;
+use super::{AgentError, FrameTracker, TickContext};
 
 #[allow(unused_variables)]
 pub trait ScriptAgent {
@@ -15,7 +19,12 @@ pub trait ScriptAgent {
         field_info: FieldInfo,
         packet_queue: &mut PacketQueue,
     ) -> Self;
-    fn tick(&mut self, game_packet: GamePacket, packet_queue: &mut PacketQueue);
+    fn tick(
+        &mut self,
+        game_packet: GamePacket,
+        context: TickContext,
+        packet_queue: &mut PacketQueue,
+    );
     fn on_match_comm(&mut self, match_comm: MatchComm, packet_queue: &mut PacketQueue) {}
     fn on_ball_prediction(
         &mut self,
@@ -32,10 +41,14 @@ pub trait ScriptAgent {
     fn on_ping_response(&mut self, ping: PingResponse, packet_queue: &mut PacketQueue) {}
 }
 
+/// If `latency_monitor` is set, the runner measures the latency to core with
+/// it and passes the stats to every tick in [TickContext::latency]. Its
+/// responses never reach the agent.
 pub fn run_script_agent<T: ScriptAgent>(
     agent_id: String,
     wants_ball_predictions: bool,
     wants_comms: bool,
+    latency_monitor: Option<LatencyMonitor>,
     mut connection: RLBotConnection,
 ) -> Result<(), AgentError> {
     connection.send_packet(ConnectionSettings {
@@ -62,11 +75,21 @@ pub fn run_script_agent<T: ScriptAgent>(
     outgoing_queue.push(InitComplete {});
     connection.send_packets_enum(outgoing_queue.empty().into_iter())?;
 
+    let mut frame_tracker = FrameTracker::new();
+    let mut latency = latency_monitor.map(LatencyMonitor::for_runner);
     while let Ok(packet) = connection.recv_packet() {
         match packet {
             CoreMessage::DisconnectSignal(_) => break,
             CoreMessage::GamePacket(x) => {
-                agent.tick(*x, &mut outgoing_queue);
+                outgoing_queue.observe_game_packet(&x);
+                if let Some(latency) = &mut latency {
+                    latency.tick(&mut outgoing_queue);
+                }
+                let context = TickContext {
+                    latency: latency.as_ref().and_then(LatencyMonitor::stats),
+                    ..frame_tracker.update(&x)
+                };
+                agent.tick(*x, context, &mut outgoing_queue);
             }
             CoreMessage::MatchComm(x) => {
                 agent.on_match_comm(*x, &mut outgoing_queue);
@@ -78,7 +101,14 @@ pub fn run_script_agent<T: ScriptAgent>(
                 agent.on_rendering_status(*x, &mut outgoing_queue);
             }
             CoreMessage::PingResponse(x) => {
-                agent.on_ping_response(*x, &mut outgoing_queue);
+                // Responses to the pings of any runner aren't forwarded
+                if latency::is_runner_cookie(x.cookie) {
+                    if let Some(latency) = &mut latency {
+                        latency.on_ping_response(&x);
+                    }
+                } else {
+                    agent.on_ping_response(*x, &mut outgoing_queue);
+                }
             }
             CoreMessage::PingRequest(x) => {
                 outgoing_queue.push(PingResponse { cookie: x.cookie });
@@ -0,0 +1,63 @@
+use rlbot_flat::flat::{GamePacket, MatchPhase};
+
+use crate::latency::LatencyStats;
+
+/// Information about the timing of a [GamePacket], compared with the packet
+/// of the previous tick.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct TickContext {
+    /// Game seconds since the previous tick. 0 on the first tick and after
+    /// the game was restarted.
+    pub delta_time: f32,
+    /// The number of frames between the previous tick and this one that
+    /// were never received.
+    pub frames_skipped: u32,
+    /// Whether this packet is for the same frame as the previous one.
+    pub is_duplicate: bool,
+    pub is_paused: bool,
+    /// Round-trip latency to core, measured by the agent runner if it was
+    /// given a [LatencyMonitor](crate::latency::LatencyMonitor). `None` until
+    /// the first ping is answered, and always `None` from [FrameTracker].
+    pub latency: Option<LatencyStats>,
+}
+
+/// Tracks [MatchInfo::frame_num](crate::flat::MatchInfo::frame_num) and
+/// [MatchInfo::seconds_elapsed](crate::flat::MatchInfo::seconds_elapsed)
+/// across packets to build a [TickContext] for every tick. The agent runners
+/// do this for you.
+#[derive(Debug, Clone, Default)]
+pub struct FrameTracker {
+    last_frame: Option<(u32, f32)>,
+}
+
+impl FrameTracker {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn update(&mut self, game_packet: &GamePacket) -> TickContext {
+        let match_info = &game_packet.match_info;
+        let frame = match_info.frame_num;
+        let seconds = match_info.seconds_elapsed;
+        let is_paused = match_info.match_phase == MatchPhase::Paused;
+
+        let context = match self.last_frame {
+            // A frame counter going backwards means the game was restarted
+            Some((last_frame, last_seconds)) if frame >= last_frame => TickContext {
+                delta_time: (seconds - last_seconds).max(0.),
+                frames_skipped: (frame - last_frame).saturating_sub(1),
+                is_duplicate: frame == last_frame,
+                is_paused,
+                latency: None,
+            },
+            _ => TickContext {
+                is_paused,
+                ..Default::default()
+            },
+        };
+
+        self.last_frame = Some((frame, seconds));
+        context
+    }
+}
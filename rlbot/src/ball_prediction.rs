@@ -0,0 +1,224 @@
+//! This module contains extension methods for querying a [BallPrediction],
+//! and [Renderer] helpers for visualizing one.
+//!
+//! Example:
+//! ```ignore
+//! use rlbot::ball_prediction::BallPredictionExt;
+//!
+//! // Where will the ball be in one second?
+//! let slice = ball_prediction.interpolate(game_packet.match_info.seconds_elapsed + 1.);
+//!
+//! // Is the ball about to go in?
+//! if let Some(entry) = ball_prediction.find_goal_entry(&field_info) {
+//!     println!("Ball enters goal of team {} at {}", entry.team, entry.slice.game_seconds);
+//! }
+//! ```
+
+use rlbot_flat::flat::{
+    BallPrediction, Color, FieldInfo, Physics, PredictionSlice, TextHAlign, TextVAlign,
+};
+use rlbot_flat::glam::{Quat, Vec3};
+
+pub use crate::constants::BALL_RADIUS;
+use crate::{constants::PhysicsConstants, render::Renderer};
+
+/// A slice where the predicted ball has fully entered a goal.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GoalEntry {
+    /// The team owning the goal that was entered, i.e. the team that
+    /// conceded.
+    pub team: u32,
+    pub slice: PredictionSlice,
+}
+
+/// Extension methods for querying a [BallPrediction].
+pub trait BallPredictionExt {
+    fn closest_slice(&self, game_seconds: f32) -> Option<&PredictionSlice>;
+    fn interpolate(&self, game_seconds: f32) -> Option<PredictionSlice>;
+    fn find_plane_crossing(
+        &self,
+        point: impl Into<Vec3>,
+        normal: impl Into<Vec3>,
+    ) -> Option<PredictionSlice>;
+    fn find_height_crossing(&self, height: f32) -> Option<PredictionSlice>;
+    fn find_goal_entry(&self, field_info: &FieldInfo) -> Option<GoalEntry>;
+    fn find_goal_entry_with(
+        &self,
+        field_info: &FieldInfo,
+        constants: &PhysicsConstants,
+    ) -> Option<GoalEntry>;
+    fn find_within_radius(&self, point: impl Into<Vec3>, radius: f32) -> Option<&PredictionSlice>;
+}
+
+impl BallPredictionExt for BallPrediction {
+    /// Get the slice at, or closest to, the given game time.
+    fn closest_slice(&self, game_seconds: f32) -> Option<&PredictionSlice> {
+        let i = self
+            .slices
+            .partition_point(|slice| slice.game_seconds < game_seconds);
+
+        match (
+            i.checked_sub(1).map(|i| &self.slices[i]),
+            self.slices.get(i),
+        ) {
+            (Some(before), Some(after)) => {
+                if game_seconds - before.game_seconds <= after.game_seconds - game_seconds {
+                    Some(before)
+                } else {
+                    Some(after)
+                }
+            }
+            (before, after) => before.or(after),
+        }
+    }
+
+    /// Get the interpolated state of the ball at the given game time.
+    /// Returns `None` if the time is outside of the predicted range.
+    fn interpolate(&self, game_seconds: f32) -> Option<PredictionSlice> {
+        let i = self
+            .slices
+            .partition_point(|slice| slice.game_seconds < game_seconds);
+        let after = self.slices.get(i)?;
+
+        if after.game_seconds == game_seconds {
+            return Some(after.clone());
+        }
+
+        let before = &self.slices[i.checked_sub(1)?];
+        let t = (game_seconds - before.game_seconds) / (after.game_seconds - before.game_seconds);
+
+        Some(lerp_slice(before, after, t))
+    }
+
+    /// Find the first time the ball center crosses the plane through `point`
+    /// with the given `normal`, in either direction. The returned slice is
+    /// interpolated to the moment of crossing.
+    fn find_plane_crossing(
+        &self,
+        point: impl Into<Vec3>,
+        normal: impl Into<Vec3>,
+    ) -> Option<PredictionSlice> {
+        let point = point.into();
+        let normal = normal.into();
+        let signed_distance =
+            |slice: &PredictionSlice| (Vec3::from(slice.physics.location) - point).dot(normal);
+
+        self.slices.windows(2).find_map(|pair| {
+            let (d0, d1) = (signed_distance(&pair[0]), signed_distance(&pair[1]));
+            if d0 == 0. {
+                return Some(pair[0].clone());
+            }
+            if (d0 < 0.) == (d1 < 0.) && d1 != 0. {
+                return None;
+            }
+            Some(lerp_slice(&pair[0], &pair[1], d0 / (d0 - d1)))
+        })
+    }
+
+    /// Find the first time the ball center crosses the given height.
+    fn find_height_crossing(&self, height: f32) -> Option<PredictionSlice> {
+        self.find_plane_crossing(Vec3::new(0., 0., height), Vec3::Z)
+    }
+
+    /// Find the first slice where the ball has fully entered one of the goals
+    /// in [FieldInfo::goals].
+    fn find_goal_entry(&self, field_info: &FieldInfo) -> Option<GoalEntry> {
+        self.find_goal_entry_with(field_info, &PhysicsConstants::default())
+    }
+
+    /// [find_goal_entry](Self::find_goal_entry) using the ball radius of the
+    /// given constants, for matches with a ball size mutator.
+    fn find_goal_entry_with(
+        &self,
+        field_info: &FieldInfo,
+        constants: &PhysicsConstants,
+    ) -> Option<GoalEntry> {
+        self.slices.iter().find_map(|slice| {
+            let location = Vec3::from(slice.physics.location);
+            field_info.goals.iter().find_map(|goal| {
+                let goal_location = Vec3::from(goal.location);
+                let direction = Vec3::from(goal.direction).normalize_or_zero();
+                let lateral = direction.cross(Vec3::Z).normalize_or_zero();
+                let offset = location - goal_location;
+
+                let inside = offset.dot(direction) < -constants.ball_radius
+                    && offset.dot(lateral).abs() < goal.width / 2.
+                    && offset.z.abs() < goal.height / 2.;
+
+                inside.then(|| GoalEntry {
+                    team: goal.team_num,
+                    slice: slice.clone(),
+                })
+            })
+        })
+    }
+
+    /// Find the first slice where the ball center is within `radius` of
+    /// `point`.
+    fn find_within_radius(&self, point: impl Into<Vec3>, radius: f32) -> Option<&PredictionSlice> {
+        let point = point.into();
+        self.slices.iter().find(|slice| {
+            Vec3::from(slice.physics.location).distance_squared(point) <= radius * radius
+        })
+    }
+}
+
+fn lerp_slice(a: &PredictionSlice, b: &PredictionSlice, t: f32) -> PredictionSlice {
+    let lerp = |a, b| Vec3::lerp(Vec3::from(a), Vec3::from(b), t).into();
+    PredictionSlice {
+        game_seconds: a.game_seconds + (b.game_seconds - a.game_seconds) * t,
+        physics: Physics {
+            location: lerp(a.physics.location, b.physics.location),
+            rotation: Quat::from(a.physics.rotation)
+                .slerp(b.physics.rotation.into(), t)
+                .into(),
+            velocity: lerp(a.physics.velocity, b.physics.velocity),
+            angular_velocity: lerp(a.physics.angular_velocity, b.physics.angular_velocity),
+        },
+    }
+}
+
+impl Renderer {
+    /// Draws the path of the predicted ball.
+    /// Only every `step`th slice is used, which reduces the size of the
+    /// render message.
+    pub fn ball_prediction(&mut self, ball_prediction: &BallPrediction, step: usize, color: Color) {
+        self.polyline_3d(
+            ball_prediction
+                .slices
+                .iter()
+                .step_by(step.max(1))
+                .map(|slice| slice.physics.location),
+            color,
+        );
+    }
+
+    /// Draws a marker on the predicted ball path for every `interval` seconds.
+    pub fn ball_prediction_markers(
+        &mut self,
+        ball_prediction: &BallPrediction,
+        interval: f32,
+        color: Color,
+    ) {
+        let Some(first) = ball_prediction.slices.first() else {
+            return;
+        };
+        let mut next_marker = first.game_seconds;
+
+        for slice in &ball_prediction.slices {
+            if slice.game_seconds < next_marker {
+                continue;
+            }
+            next_marker += interval;
+
+            self.rect_3d(
+                slice.physics.location,
+                0.01,
+                0.01,
+                color,
+                TextHAlign::Center,
+                TextVAlign::Center,
+            );
+        }
+    }
+}
@@ -0,0 +1,278 @@
+//! This module contains [BallSimulator], a small ball physics simulator for
+//! predicting hypothetical states, such as the ball right after a planned
+//! touch.
+//!
+//! The arena is approximated by the planes of the standard soccar field:
+//! floor, ceiling, side walls, back walls, corner walls, and the goals.
+//! Rounded edges aren't modelled, so predictions diverge from core's after
+//! bounces off the curved parts of the arena. Use [compare] to measure how
+//! far off a prediction is from a [BallPrediction] sent by core.
+//!
+//! Example:
+//! ```rust
+//! use rlbot::ball_sim::BallSimulator;
+//! use rlbot::flat::{Physics, Vector3};
+//!
+//! let ball = Physics {
+//!     location: Vector3 { x: 0., y: 0., z: 1000. },
+//!     ..Default::default()
+//! };
+//!
+//! let slices = BallSimulator::default().predict(&ball, 0., 3., 1. / 120.);
+//!
+//! // The ball falls, bounces, and never goes through the floor
+//! assert!(slices.iter().all(|slice| slice.physics.location.z > 0.));
+//! assert!(slices.iter().any(|slice| slice.physics.velocity.z > 0.));
+//! ```
+
+use rlbot_flat::{
+    flat::{BallPrediction, Physics, PredictionSlice},
+    glam::{Quat, Vec3},
+};
+
+use crate::{
+    ball_prediction::BallPredictionExt,
+    constants::PhysicsConstants,
+    field::{
+        BACK_WALL_Y, CEILING_Z, CORNER_WALL_XY, GOAL_DEPTH, GOAL_HALF_WIDTH, GOAL_HEIGHT,
+        SIDE_WALL_X,
+    },
+};
+
+/// The fraction of its velocity the ball loses to air drag every second.
+const BALL_DRAG: f32 = 0.0305;
+const BALL_MAX_ANGULAR_SPEED: f32 = 6.;
+/// Friction between the ball and the arena when bouncing.
+const BALL_FRICTION: f32 = 0.35;
+/// Contacts slower than this don't bounce.
+const REST_SPEED: f32 = 25.;
+/// Core runs physics at 120 ticks per second.
+const TICK_RATE: f32 = 120.;
+/// The maximum number of contacts resolved per step.
+const MAX_CONTACTS: usize = 3;
+
+#[derive(Debug, Clone, Copy)]
+struct BallState {
+    location: Vec3,
+    rotation: Quat,
+    velocity: Vec3,
+    angular_velocity: Vec3,
+}
+
+impl From<&Physics> for BallState {
+    fn from(physics: &Physics) -> Self {
+        Self {
+            location: physics.location.into(),
+            rotation: physics.rotation.into(),
+            velocity: physics.velocity.into(),
+            angular_velocity: physics.angular_velocity.into(),
+        }
+    }
+}
+
+impl From<BallState> for Physics {
+    fn from(state: BallState) -> Self {
+        Self {
+            location: state.location.into(),
+            rotation: state.rotation.into(),
+            velocity: state.velocity.into(),
+            angular_velocity: state.angular_velocity.into(),
+        }
+    }
+}
+
+/// Simulates the ball with the given [PhysicsConstants].
+#[derive(Debug, Clone, Default)]
+pub struct BallSimulator {
+    pub constants: PhysicsConstants,
+}
+
+impl BallSimulator {
+    #[must_use]
+    pub fn new(constants: PhysicsConstants) -> Self {
+        Self { constants }
+    }
+
+    /// Advance the ball by `dt` seconds.
+    pub fn step(&self, physics: &mut Physics, dt: f32) {
+        let mut state = BallState::from(&*physics);
+        self.step_state(&mut state, dt);
+        *physics = state.into();
+    }
+
+    /// Predict the ball starting at `game_seconds`, for `duration` seconds.
+    /// The ball is simulated at core's tick rate, and a slice is output every
+    /// `interval` seconds.
+    #[must_use]
+    pub fn predict(
+        &self,
+        physics: &Physics,
+        game_seconds: f32,
+        duration: f32,
+        interval: f32,
+    ) -> Vec<PredictionSlice> {
+        let dt = 1. / TICK_RATE;
+        let steps_per_slice = (interval * TICK_RATE).round().max(1.) as usize;
+        let num_slices = (duration / (steps_per_slice as f32 * dt)).floor() as usize;
+
+        let mut state = BallState::from(physics);
+        (1..=num_slices)
+            .map(|i| {
+                for _ in 0..steps_per_slice {
+                    self.step_state(&mut state, dt);
+                }
+                PredictionSlice {
+                    game_seconds: game_seconds + (i * steps_per_slice) as f32 * dt,
+                    physics: state.into(),
+                }
+            })
+            .collect()
+    }
+
+    /// [predict](Self::predict) with core's horizon and resolution: six
+    /// seconds at 120 slices per second.
+    #[must_use]
+    pub fn predict_like_core(&self, physics: &Physics, game_seconds: f32) -> BallPrediction {
+        BallPrediction {
+            slices: self.predict(physics, game_seconds, 6., 1. / TICK_RATE),
+        }
+    }
+
+    fn step_state(&self, state: &mut BallState, dt: f32) {
+        let constants = &self.constants;
+
+        state.velocity.z += constants.gravity_z * dt;
+        state.velocity *= 1. - BALL_DRAG * dt;
+        state.velocity = state.velocity.clamp_length_max(constants.ball_max_speed);
+        state.angular_velocity = state
+            .angular_velocity
+            .clamp_length_max(BALL_MAX_ANGULAR_SPEED);
+
+        state.location += state.velocity * dt;
+        let angle = state.angular_velocity * dt;
+        if angle != Vec3::ZERO {
+            state.rotation = (Quat::from_scaled_axis(angle) * state.rotation).normalize();
+        }
+
+        for _ in 0..MAX_CONTACTS {
+            let Some((normal, penetration)) =
+                deepest_contact(state.location, constants.ball_radius)
+            else {
+                break;
+            };
+
+            state.location += normal * penetration;
+
+            let normal_speed = state.velocity.dot(normal);
+            if normal_speed >= 0. {
+                continue;
+            }
+
+            // Slow contacts, like a ball resting on the floor, don't bounce
+            let restitution = if -normal_speed < REST_SPEED {
+                0.
+            } else {
+                constants.ball_restitution
+            };
+            let impulse = -(1. + restitution) * normal_speed;
+            state.velocity += normal * impulse;
+
+            // Friction acts on the slip of the contact point, so a rolling
+            // ball isn't slowed down. Stopping the slip of a solid sphere
+            // takes 2/7 of it in linear velocity, the rest comes from spin.
+            let contact = -normal * constants.ball_radius;
+            let slip = state.velocity + state.angular_velocity.cross(contact);
+            let slip = slip - normal * slip.dot(normal);
+            let friction = (BALL_FRICTION * impulse).min(slip.length() * 2. / 7.);
+            let change = -slip.normalize_or_zero() * friction;
+
+            state.velocity += change;
+            state.angular_velocity -= normal.cross(change) * 2.5 / constants.ball_radius;
+        }
+    }
+}
+
+/// The contact with the arena that penetrates the ball the most, as the
+/// normal pointing into the arena and the penetration depth.
+fn deepest_contact(location: Vec3, radius: f32) -> Option<(Vec3, f32)> {
+    let Vec3 { x, y, z } = location;
+    let sign_x = x.signum();
+    let sign_y = y.signum();
+
+    let in_goal_mouth = x.abs() < GOAL_HALF_WIDTH && z < GOAL_HEIGHT;
+    let in_goal = y.abs() > BACK_WALL_Y && in_goal_mouth;
+
+    let mut contacts = vec![(Vec3::Z, radius - z), (Vec3::NEG_Z, z + radius - CEILING_Z)];
+
+    if in_goal {
+        contacts.extend([
+            (
+                Vec3::new(-sign_x, 0., 0.),
+                x.abs() + radius - GOAL_HALF_WIDTH,
+            ),
+            (Vec3::NEG_Z, z + radius - GOAL_HEIGHT),
+            (
+                Vec3::new(0., -sign_y, 0.),
+                y.abs() + radius - (BACK_WALL_Y + GOAL_DEPTH),
+            ),
+        ]);
+    } else {
+        contacts.extend([
+            (Vec3::new(-sign_x, 0., 0.), x.abs() + radius - SIDE_WALL_X),
+            (
+                Vec3::new(-sign_x, -sign_y, 0.).normalize(),
+                radius - (CORNER_WALL_XY - x.abs() - y.abs()) / 2f32.sqrt(),
+            ),
+        ]);
+
+        if !in_goal_mouth {
+            contacts.push((Vec3::new(0., -sign_y, 0.), y.abs() + radius - BACK_WALL_Y));
+        }
+    }
+
+    contacts
+        .into_iter()
+        .filter(|&(_, penetration)| penetration > 0.)
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+}
+
+/// How far a simulated prediction is from a reference prediction.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PredictionError {
+    pub mean_distance: f32,
+    pub max_distance: f32,
+    /// The game time of the slice with the largest error.
+    pub max_distance_seconds: f32,
+    /// The number of slices that could be compared.
+    pub samples: usize,
+}
+
+/// Compare simulated slices with a reference prediction, such as one sent by
+/// core for the same starting state. Slices outside of the time range of the
+/// reference are skipped. Returns `None` if no slices overlap.
+#[must_use]
+pub fn compare(
+    simulated: &[PredictionSlice],
+    reference: &BallPrediction,
+) -> Option<PredictionError> {
+    let distances: Vec<_> = simulated
+        .iter()
+        .filter_map(|slice| {
+            let expected = reference.interpolate(slice.game_seconds)?;
+            let distance =
+                Vec3::from(slice.physics.location).distance(expected.physics.location.into());
+            Some((slice.game_seconds, distance))
+        })
+        .collect();
+
+    let &(max_distance_seconds, max_distance) =
+        distances.iter().max_by(|a, b| a.1.total_cmp(&b.1))?;
+
+    Some(PredictionError {
+        mean_distance: distances.iter().map(|(_, distance)| distance).sum::<f32>()
+            / distances.len() as f32,
+        max_distance,
+        max_distance_seconds,
+        samples: distances.len(),
+    })
+}
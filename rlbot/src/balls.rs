@@ -0,0 +1,205 @@
+//! This module contains helpers for matches with more than one ball: finding
+//! the nearest ball, ranking balls by how threatening they are, and tracking
+//! the identity of balls across packets.
+//!
+//! Example:
+//! ```ignore
+//! use rlbot::balls::{BallTracker, nearest_ball, rank_threats};
+//!
+//! let mut balls = BallTracker::new();
+//!
+//! // In tick:
+//! balls.update(&game_packet);
+//! let (index, _) = nearest_ball(&game_packet, car_location).unwrap();
+//! let threats = rank_threats(&game_packet, field.own_goal(team).unwrap());
+//!
+//! renderer.ball_labels(&balls, colors::WHITE);
+//! ```
+
+use rlbot_flat::{
+    flat::{BallAnchor, BallInfo, Color, GamePacket, TextHAlign, TextVAlign},
+    glam::Vec3,
+};
+
+use crate::{
+    field::Goal,
+    modes::BallShape,
+    render::{Renderer, colors},
+};
+
+/// Balls that moved further than this from their expected location between
+/// two packets are considered different balls.
+const MAX_MATCH_DISTANCE: f32 = 500.;
+
+/// The ball nearest to `point`, with its index.
+#[must_use]
+pub fn nearest_ball(
+    game_packet: &GamePacket,
+    point: impl Into<Vec3>,
+) -> Option<(usize, &BallInfo)> {
+    let point = point.into();
+    game_packet
+        .balls
+        .iter()
+        .enumerate()
+        .min_by(|(_, a), (_, b)| {
+            let distance =
+                |ball: &BallInfo| Vec3::from(ball.physics.location).distance_squared(point);
+            distance(a).total_cmp(&distance(b))
+        })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BallThreat {
+    pub ball_index: usize,
+    /// Seconds until the ball reaches the plane of the goal mouth, if it
+    /// keeps its current velocity.
+    pub time_to_goal: f32,
+}
+
+/// The balls heading towards `goal`, most urgent first.
+///
+/// Only the velocity towards the goal is considered, so bounces and gravity
+/// are ignored.
+#[must_use]
+pub fn rank_threats(game_packet: &GamePacket, goal: &Goal) -> Vec<BallThreat> {
+    let mut threats: Vec<_> = game_packet
+        .balls
+        .iter()
+        .enumerate()
+        .filter_map(|(ball_index, ball)| {
+            let location = Vec3::from(ball.physics.location);
+            let velocity = Vec3::from(ball.physics.velocity);
+
+            // The direction of the goal points into the field
+            let distance = (location - goal.location).dot(goal.direction)
+                - BallShape::of(ball).horizontal_radius();
+            let speed = -velocity.dot(goal.direction);
+            (speed > 0.).then(|| BallThreat {
+                ball_index,
+                time_to_goal: distance.max(0.) / speed,
+            })
+        })
+        .collect();
+
+    threats.sort_by(|a, b| a.time_to_goal.total_cmp(&b.time_to_goal));
+    threats
+}
+
+/// An identifier of a ball that stays the same while the ball exists, even
+/// when the order of [GamePacket::balls] changes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BallId(pub u64);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct TrackedBall {
+    id: BallId,
+    location: Vec3,
+    velocity: Vec3,
+    /// The distance from the center of the ball to its top.
+    half_height: f32,
+}
+
+/// Keeps [BallId]s stable across packets by matching every ball to where a
+/// ball of the previous packet was expected to be.
+#[derive(Debug, Clone, Default)]
+pub struct BallTracker {
+    balls: Vec<TrackedBall>,
+    next_id: u64,
+    last_seconds: Option<f32>,
+}
+
+impl BallTracker {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn update(&mut self, game_packet: &GamePacket) {
+        let now = game_packet.match_info.seconds_elapsed;
+        let dt = self.last_seconds.map_or(0., |last| (now - last).max(0.));
+        self.last_seconds = Some(now);
+
+        let mut unmatched: Vec<_> = self
+            .balls
+            .iter()
+            .map(|ball| (ball.id, ball.location + ball.velocity * dt))
+            .collect();
+
+        self.balls = game_packet
+            .balls
+            .iter()
+            .map(|ball| {
+                let location = Vec3::from(ball.physics.location);
+
+                let closest = unmatched
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &(_, expected))| (i, expected.distance(location)))
+                    .filter(|&(_, distance)| distance < MAX_MATCH_DISTANCE)
+                    .min_by(|a, b| a.1.total_cmp(&b.1));
+
+                let id = match closest {
+                    Some((i, _)) => unmatched.swap_remove(i).0,
+                    None => {
+                        self.next_id += 1;
+                        BallId(self.next_id)
+                    }
+                };
+
+                TrackedBall {
+                    id,
+                    location,
+                    velocity: ball.physics.velocity.into(),
+                    half_height: BallShape::of(ball).rest_height(),
+                }
+            })
+            .collect();
+    }
+
+    /// The id of every ball, in the order of [GamePacket::balls].
+    pub fn ids(&self) -> impl Iterator<Item = BallId> + '_ {
+        self.balls.iter().map(|ball| ball.id)
+    }
+
+    /// The id of the ball at the given index of [GamePacket::balls].
+    #[must_use]
+    pub fn id_of(&self, index: usize) -> Option<BallId> {
+        self.balls.get(index).map(|ball| ball.id)
+    }
+
+    /// The current index in [GamePacket::balls] of the ball.
+    #[must_use]
+    pub fn index_of(&self, id: BallId) -> Option<usize> {
+        self.balls.iter().position(|ball| ball.id == id)
+    }
+
+    /// A render anchor following the ball, offset by `local`.
+    #[must_use]
+    pub fn anchor(&self, id: BallId, local: impl Into<Vec3>) -> Option<BallAnchor> {
+        Some(BallAnchor {
+            index: self.index_of(id)? as u32,
+            local: local.into().into(),
+        })
+    }
+}
+
+impl Renderer {
+    /// Draws the [BallId] of every ball above it.
+    pub fn ball_labels(&mut self, tracker: &BallTracker, color: Color) {
+        for (index, ball) in tracker.balls.iter().enumerate() {
+            self.string_3d(
+                format!("Ball {}", ball.id.0),
+                BallAnchor {
+                    index: index as u32,
+                    local: Vec3::new(0., 0., ball.half_height * 2.).into(),
+                },
+                1.,
+                color,
+                colors::TRANSPARENT,
+                TextHAlign::Center,
+                TextVAlign::Bottom,
+            );
+        }
+    }
+}
@@ -0,0 +1,196 @@
+//! This module contains [BoostPadTracker], which joins the pads of
+//! [FieldInfo](crate::flat::FieldInfo) with their state in
+//! [GamePacket::boost_pads].
+//!
+//! Example:
+//! ```ignore
+//! use rlbot::boost_pads::BoostPadTracker;
+//! use rlbot::field::{BoostPadKind, Field};
+//!
+//! let mut tracker = BoostPadTracker::new(&Field::new(&field_info));
+//!
+//! // In tick:
+//! for pickup in tracker.update(&game_packet) {
+//!     println!("Pad {} taken by {:?}", pickup.pad_index, pickup.player_index);
+//! }
+//! let pad = tracker.nearest_active(car_location, Some(BoostPadKind::Big));
+//! ```
+
+use rlbot_flat::{flat::GamePacket, glam::Vec3};
+
+use crate::field::{BoostPad, BoostPadKind, Field};
+
+/// Seconds it takes for a big pad to respawn after being picked up.
+pub const BIG_PAD_RESPAWN_TIME: f32 = 10.;
+/// Seconds it takes for a small pad to respawn after being picked up.
+pub const SMALL_PAD_RESPAWN_TIME: f32 = 4.;
+
+/// Cars further away than this from a pad are never credited with picking it
+/// up.
+const MAX_PICKUP_DISTANCE: f32 = 300.;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrackedBoostPad {
+    pub pad: BoostPad,
+    pub is_active: bool,
+    /// Seconds since the pad was picked up.
+    pub timer: f32,
+}
+
+impl TrackedBoostPad {
+    /// Seconds until this pad is active again. Zero if it's already active.
+    #[must_use]
+    pub fn time_until_respawn(&self) -> f32 {
+        if self.is_active {
+            return 0.;
+        }
+
+        let respawn_time = match self.pad.kind {
+            BoostPadKind::Big => BIG_PAD_RESPAWN_TIME,
+            BoostPadKind::Small => SMALL_PAD_RESPAWN_TIME,
+        };
+        (respawn_time - self.timer).max(0.)
+    }
+}
+
+/// A pad that went from active to inactive between two packets.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoostPadPickup {
+    pub pad_index: usize,
+    /// The index of the car that most likely picked up the pad, if any car
+    /// was close enough.
+    pub player_index: Option<usize>,
+    pub game_seconds: f32,
+}
+
+/// Tracks the state of all boost pads by diffing consecutive [GamePacket]s.
+pub struct BoostPadTracker {
+    pads: Vec<TrackedBoostPad>,
+    prev_boost: Vec<f32>,
+    initialized: bool,
+}
+
+impl BoostPadTracker {
+    #[must_use]
+    pub fn new(field: &Field) -> Self {
+        Self {
+            pads: field
+                .boost_pads
+                .iter()
+                .map(|pad| TrackedBoostPad {
+                    pad: pad.clone(),
+                    is_active: true,
+                    timer: 0.,
+                })
+                .collect(),
+            prev_boost: Vec::new(),
+            initialized: false,
+        }
+    }
+
+    /// Update the tracker with a new packet, returning the pads that were
+    /// picked up since the previous one.
+    pub fn update(&mut self, game_packet: &GamePacket) -> Vec<BoostPadPickup> {
+        let mut pickups = Vec::new();
+
+        for (pad, state) in self.pads.iter_mut().zip(&game_packet.boost_pads) {
+            if self.initialized && pad.is_active && !state.is_active {
+                pickups.push(BoostPadPickup {
+                    pad_index: pad.pad.index,
+                    player_index: likely_pickup_player(&pad.pad, game_packet, &self.prev_boost),
+                    game_seconds: game_packet.match_info.seconds_elapsed,
+                });
+            }
+
+            pad.is_active = state.is_active;
+            pad.timer = state.timer;
+        }
+
+        self.prev_boost.clear();
+        self.prev_boost
+            .extend(game_packet.players.iter().map(|player| player.boost));
+        self.initialized = true;
+
+        pickups
+    }
+
+    #[must_use]
+    pub fn pads(&self) -> &[TrackedBoostPad] {
+        &self.pads
+    }
+
+    /// Get a pad by its index in [GamePacket::boost_pads].
+    #[must_use]
+    pub fn get(&self, index: usize) -> Option<&TrackedBoostPad> {
+        self.pads.get(index)
+    }
+
+    pub fn active_pads(&self) -> impl Iterator<Item = &TrackedBoostPad> {
+        self.pads.iter().filter(|pad| pad.is_active)
+    }
+
+    /// The active pad nearest to `point`, optionally only considering pads of
+    /// the given kind.
+    #[must_use]
+    pub fn nearest_active(
+        &self,
+        point: impl Into<Vec3>,
+        kind: Option<BoostPadKind>,
+    ) -> Option<&TrackedBoostPad> {
+        let point = point.into();
+        self.active_pads_of_kind(kind).min_by(|a, b| {
+            a.pad
+                .location
+                .distance_squared(point)
+                .total_cmp(&b.pad.location.distance_squared(point))
+        })
+    }
+
+    /// The active pad that adds the shortest detour to a straight path from
+    /// `start` to `end`, optionally only considering pads of the given kind.
+    #[must_use]
+    pub fn nearest_active_along(
+        &self,
+        start: impl Into<Vec3>,
+        end: impl Into<Vec3>,
+        kind: Option<BoostPadKind>,
+    ) -> Option<&TrackedBoostPad> {
+        let (start, end) = (start.into(), end.into());
+        let detour = |pad: &TrackedBoostPad| {
+            start.distance(pad.pad.location) + pad.pad.location.distance(end)
+        };
+        self.active_pads_of_kind(kind)
+            .min_by(|a, b| detour(a).total_cmp(&detour(b)))
+    }
+
+    fn active_pads_of_kind(
+        &self,
+        kind: Option<BoostPadKind>,
+    ) -> impl Iterator<Item = &TrackedBoostPad> {
+        self.active_pads()
+            .filter(move |pad| kind.is_none_or(|kind| pad.pad.kind == kind))
+    }
+}
+
+/// The car closest to the pad, preferring cars that gained boost since the
+/// previous packet.
+fn likely_pickup_player(
+    pad: &BoostPad,
+    game_packet: &GamePacket,
+    prev_boost: &[f32],
+) -> Option<usize> {
+    let candidates = game_packet
+        .players
+        .iter()
+        .enumerate()
+        .map(|(i, player)| {
+            let gained_boost = prev_boost.get(i).is_some_and(|&prev| player.boost > prev);
+            let distance = pad.location.distance(player.physics.location.into());
+            (i, gained_boost, distance)
+        })
+        .filter(|&(_, _, distance)| distance <= MAX_PICKUP_DISTANCE);
+
+    candidates
+        .min_by(|a, b| b.1.cmp(&a.1).then(a.2.total_cmp(&b.2)))
+        .map(|(i, _, _)| i)
+}
@@ -0,0 +1,385 @@
+//! This module contains [CarSimulator], a simple car physics simulator for
+//! evaluating maneuvers offline.
+//!
+//! It models driving on the floor with throttle and boost curves and a
+//! speed-dependent turning radius, jumps, double jumps and dodges, and aerial
+//! rotation. Driving on walls and collisions with the arena aren't modelled.
+//! Use [CarSimulator::error_against] to measure how far off the simulation is
+//! from a recording of packets.
+//!
+//! Example:
+//! ```rust
+//! use rlbot::car_sim::{CarSimulator, CarState};
+//! use rlbot::flat::ControllerState;
+//!
+//! let simulator = CarSimulator::default();
+//! let mut car = CarState::default();
+//! let controls = ControllerState { boost: true, ..Default::default() };
+//!
+//! for _ in 0..120 {
+//!     simulator.step(&mut car, &controls, 1. / 120.);
+//! }
+//!
+//! // A second of boosting from a standstill gets a car well past driving speed
+//! assert!(car.velocity.x > 1500.);
+//! assert!(car.boost < 100.);
+//! ```
+
+use rlbot_flat::{
+    flat::{AirState, ControllerState, GamePacket, Physics, PlayerInfo},
+    glam::{Mat3, Quat, Vec3},
+};
+
+use crate::{
+    constants::{CAR_REST_HEIGHT, PhysicsConstants},
+    controller::{ActionSequence, ControllerStateExt},
+    player::MAX_DRIVE_SPEED,
+};
+
+const CAR_MAX_ANGULAR_SPEED: f32 = 5.5;
+
+/// Throttle acceleration at the given speeds, linearly interpolated.
+const THROTTLE_CURVE: [(f32, f32); 3] = [(0., 1600.), (1400., 160.), (MAX_DRIVE_SPEED, 0.)];
+const BRAKE_ACCELERATION: f32 = 3500.;
+const COAST_ACCELERATION: f32 = 525.;
+/// Boost used per second while boosting.
+const BOOST_CONSUMPTION: f32 = 33.3;
+
+/// Turning curvature at the given speeds, linearly interpolated.
+const CURVATURE_CURVE: [(f32, f32); 6] = [
+    (0., 0.0069),
+    (500., 0.00398),
+    (1000., 0.00235),
+    (1500., 0.001375),
+    (1750., 0.0011),
+    (2300., 0.00088),
+];
+
+const JUMP_IMPULSE: f32 = 291.667;
+/// Acceleration while holding jump, for up to [JUMP_HOLD_TIME].
+const JUMP_HOLD_ACCELERATION: f32 = 1458.333;
+const JUMP_HOLD_TIME: f32 = 0.2;
+/// Seconds after jumping during which a second jump or dodge is available.
+const SECOND_JUMP_WINDOW: f32 = 1.25;
+const DODGE_IMPULSE: f32 = 500.;
+/// Stick inputs below this don't turn a second jump into a dodge.
+const DODGE_DEADZONE: f32 = 0.5;
+
+/// Aerial torque and damping for roll, pitch, and yaw, in radians per second
+/// squared.
+const ROLL_TORQUE: f32 = 36.08;
+const PITCH_TORQUE: f32 = 12.146;
+const YAW_TORQUE: f32 = 8.92;
+const ROLL_DAMPING: f32 = 4.472;
+const PITCH_DAMPING: f32 = 2.798;
+const YAW_DAMPING: f32 = 1.886;
+
+/// The simulated state of a car.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CarState {
+    pub location: Vec3,
+    pub rotation: Quat,
+    pub velocity: Vec3,
+    pub angular_velocity: Vec3,
+    pub boost: f32,
+    pub on_ground: bool,
+    pub has_jumped: bool,
+    pub has_double_jumped: bool,
+    pub has_dodged: bool,
+    /// Seconds since the first jump.
+    pub jump_time: f32,
+    /// Whether jump is still held since the first jump.
+    pub holding_jump: bool,
+    /// Whether jump was pressed in the previous step.
+    pub jump_pressed: bool,
+}
+
+impl Default for CarState {
+    /// A car standing still at the center of the field, facing the orange
+    /// goal, with full boost.
+    fn default() -> Self {
+        Self {
+            location: Vec3::new(0., 0., CAR_REST_HEIGHT),
+            rotation: Quat::IDENTITY,
+            velocity: Vec3::ZERO,
+            angular_velocity: Vec3::ZERO,
+            boost: 100.,
+            on_ground: true,
+            has_jumped: false,
+            has_double_jumped: false,
+            has_dodged: false,
+            jump_time: 0.,
+            holding_jump: false,
+            jump_pressed: false,
+        }
+    }
+}
+
+impl CarState {
+    #[must_use]
+    pub fn from_player(player: &PlayerInfo) -> Self {
+        Self {
+            location: player.physics.location.into(),
+            rotation: player.physics.rotation.into(),
+            velocity: player.physics.velocity.into(),
+            angular_velocity: player.physics.angular_velocity.into(),
+            boost: player.boost,
+            on_ground: player.air_state == AirState::OnGround,
+            has_jumped: player.has_jumped,
+            has_double_jumped: player.has_double_jumped,
+            has_dodged: player.has_dodged,
+            jump_time: 0.,
+            holding_jump: player.air_state == AirState::Jumping,
+            jump_pressed: player.last_input.jump,
+        }
+    }
+
+    #[must_use]
+    pub fn physics(&self) -> Physics {
+        Physics {
+            location: self.location.into(),
+            rotation: self.rotation.into(),
+            velocity: self.velocity.into(),
+            angular_velocity: self.angular_velocity.into(),
+        }
+    }
+
+    /// The orientation matrix, with forward, right, and up as columns.
+    #[must_use]
+    pub fn orientation(&self) -> Mat3 {
+        Mat3::from_quat(self.rotation)
+    }
+}
+
+/// Simulates a car with the given [PhysicsConstants].
+#[derive(Debug, Clone, Default)]
+pub struct CarSimulator {
+    pub constants: PhysicsConstants,
+}
+
+impl CarSimulator {
+    #[must_use]
+    pub fn new(constants: PhysicsConstants) -> Self {
+        Self { constants }
+    }
+
+    /// Advance the car by `dt` seconds with the given inputs.
+    pub fn step(&self, state: &mut CarState, controls: &ControllerState, dt: f32) {
+        let mut controls = controls.clamped();
+        let jump_started = controls.jump && !state.jump_pressed;
+        state.jump_pressed = controls.jump;
+
+        let boosting = controls.boost
+            && self.constants.boost_available
+            && (state.boost > 0. || self.constants.unlimited_boost);
+        if boosting {
+            // Boosting always throttles fully
+            controls.throttle = 1.;
+        }
+
+        if state.on_ground {
+            self.step_ground(state, &controls, jump_started, dt);
+        } else {
+            self.step_air(state, &controls, jump_started, dt);
+        }
+
+        if boosting {
+            state.velocity += state.orientation().x_axis * self.constants.boost_acceleration * dt;
+            if !self.constants.unlimited_boost {
+                state.boost = (state.boost - BOOST_CONSUMPTION * dt).max(0.);
+            }
+        }
+
+        state.velocity = state
+            .velocity
+            .clamp_length_max(self.constants.car_max_speed);
+        state.angular_velocity = state
+            .angular_velocity
+            .clamp_length_max(CAR_MAX_ANGULAR_SPEED);
+
+        state.location += state.velocity * dt;
+        let angle = state.angular_velocity * dt;
+        if angle != Vec3::ZERO {
+            state.rotation = (Quat::from_scaled_axis(angle) * state.rotation).normalize();
+        }
+
+        if !state.on_ground && state.location.z <= CAR_REST_HEIGHT && state.velocity.z <= 0. {
+            land(state);
+        }
+    }
+
+    fn step_ground(
+        &self,
+        state: &mut CarState,
+        controls: &ControllerState,
+        jump_started: bool,
+        dt: f32,
+    ) {
+        let forward = state.orientation().x_axis;
+        let speed = state.velocity.dot(forward);
+
+        let acceleration = if controls.throttle == 0. {
+            -speed.signum() * COAST_ACCELERATION.min(speed.abs() / dt)
+        } else if speed * controls.throttle < 0. {
+            controls.throttle.signum() * BRAKE_ACCELERATION.min(speed.abs() / dt)
+        } else {
+            controls.throttle * interpolate(&THROTTLE_CURVE, speed.abs())
+        };
+        let speed = speed + acceleration * dt;
+
+        // Turn without sliding, so the velocity follows the heading
+        let yaw_rate = controls.steer * interpolate(&CURVATURE_CURVE, speed.abs()) * speed;
+        state.angular_velocity = Vec3::Z * yaw_rate;
+        let yaw = Quat::from_rotation_z(yaw_rate * dt);
+        state.velocity = yaw * forward * speed;
+        state.velocity.z = 0.;
+
+        if jump_started {
+            let up = state.orientation().z_axis;
+            state.velocity += up * JUMP_IMPULSE;
+            state.on_ground = false;
+            state.has_jumped = true;
+            state.holding_jump = true;
+            state.jump_time = 0.;
+        }
+    }
+
+    fn step_air(
+        &self,
+        state: &mut CarState,
+        controls: &ControllerState,
+        jump_started: bool,
+        dt: f32,
+    ) {
+        let orientation = state.orientation();
+        state.jump_time += dt;
+        state.velocity.z += self.constants.gravity_z * dt;
+
+        state.holding_jump &= controls.jump && state.jump_time < JUMP_HOLD_TIME;
+        if state.holding_jump {
+            state.velocity += orientation.z_axis * JUMP_HOLD_ACCELERATION * dt;
+        }
+
+        let can_second_jump = !state.has_double_jumped
+            && !state.has_dodged
+            && (!state.has_jumped || state.jump_time < SECOND_JUMP_WINDOW);
+        if jump_started && can_second_jump {
+            let stick = Vec3::new(-controls.pitch, controls.yaw + controls.roll, 0.);
+            if stick.x.abs() + stick.y.abs() >= DODGE_DEADZONE {
+                let direction = stick.normalize();
+                let forward = orientation.x_axis.with_z(0.).normalize_or_zero();
+                let right = orientation.y_axis.with_z(0.).normalize_or_zero();
+                state.velocity += (forward * direction.x + right * direction.y) * DODGE_IMPULSE;
+                state.has_dodged = true;
+            } else {
+                state.velocity += orientation.z_axis * JUMP_IMPULSE;
+                state.has_double_jumped = true;
+            }
+            return;
+        }
+
+        // Angular velocity around the forward, right, and up axes
+        let local = orientation.transpose() * state.angular_velocity;
+        let acceleration = Vec3::new(
+            -ROLL_TORQUE * controls.roll - ROLL_DAMPING * local.x,
+            -PITCH_TORQUE * controls.pitch - PITCH_DAMPING * local.y * (1. - controls.pitch.abs()),
+            YAW_TORQUE * controls.yaw - YAW_DAMPING * local.z * (1. - controls.yaw.abs()),
+        );
+        state.angular_velocity += orientation * acceleration * dt;
+    }
+
+    /// Simulate the car while following the sequence, one state per step,
+    /// until the sequence is finished. Returns no states if `dt` isn't
+    /// positive.
+    #[must_use]
+    pub fn simulate_sequence(
+        &self,
+        mut state: CarState,
+        mut sequence: ActionSequence,
+        dt: f32,
+    ) -> Vec<CarState> {
+        let mut states = Vec::new();
+        let mut time = 0.;
+        if dt.is_nan() || dt <= 0. {
+            return states;
+        }
+
+        while let Some(controls) = sequence.tick(time) {
+            self.step(&mut state, &controls, dt);
+            states.push(state);
+            time += dt;
+        }
+
+        states
+    }
+
+    /// Replay a recording of packets. Every packet is simulated forward to
+    /// the time of the next one, using the last inputs of the player, and
+    /// compared with it. Returns `None` if the recording contains fewer than
+    /// two packets with the player.
+    #[must_use]
+    pub fn error_against(
+        &self,
+        recording: &[GamePacket],
+        player_index: usize,
+    ) -> Option<SimulationError> {
+        let distances: Vec<_> = recording
+            .windows(2)
+            .filter_map(|pair| {
+                let before = pair[0].players.get(player_index)?;
+                let after = pair[1].players.get(player_index)?;
+                let dt = pair[1].match_info.seconds_elapsed - pair[0].match_info.seconds_elapsed;
+                if dt <= 0. {
+                    return None;
+                }
+
+                let mut state = CarState::from_player(before);
+                self.step(&mut state, &after.last_input, dt);
+                Some(state.location.distance(after.physics.location.into()))
+            })
+            .collect();
+
+        if distances.is_empty() {
+            return None;
+        }
+
+        Some(SimulationError {
+            mean_distance: distances.iter().sum::<f32>() / distances.len() as f32,
+            max_distance: distances.iter().copied().fold(0., f32::max),
+            samples: distances.len(),
+        })
+    }
+}
+
+/// How far the simulation is from a recording, see
+/// [CarSimulator::error_against].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SimulationError {
+    pub mean_distance: f32,
+    pub max_distance: f32,
+    pub samples: usize,
+}
+
+fn land(state: &mut CarState) {
+    state.location.z = CAR_REST_HEIGHT;
+    state.velocity.z = 0.;
+    state.on_ground = true;
+    state.has_jumped = false;
+    state.has_double_jumped = false;
+    state.has_dodged = false;
+    state.holding_jump = false;
+
+    // Land on the wheels, keeping only the yaw
+    let forward = state.orientation().x_axis;
+    state.rotation = Quat::from_rotation_z(forward.y.atan2(forward.x));
+    state.angular_velocity = Vec3::ZERO;
+}
+
+fn interpolate(curve: &[(f32, f32)], x: f32) -> f32 {
+    let i = curve.partition_point(|&(point, _)| point < x);
+    match (i.checked_sub(1).map(|i| curve[i]), curve.get(i)) {
+        (Some((x0, y0)), Some(&(x1, y1))) => y0 + (y1 - y0) * (x - x0) / (x1 - x0),
+        (None, Some(&(_, y))) | (Some((_, y)), None) => y,
+        (None, None) => 0.,
+    }
+}
@@ -0,0 +1,228 @@
+//! This module contains a typed protocol for team communication on top of
+//! [MatchComm].
+//!
+//! Every message is prefixed with a small header containing a magic number,
+//! the [TeamMessage::KIND] and the [TeamMessage::VERSION] of the message, so
+//! other [MatchComm]s and incompatible versions are rejected when decoding.
+//!
+//! Example:
+//! ```ignore
+//! use rlbot::comms::{TeamComms, TeamIntent};
+//!
+//! let comms = TeamComms::new(self.index, self.team);
+//!
+//! // In tick:
+//! comms.send(&TeamIntent::GoingForBall { eta: 1.2 }, true, packet_queue);
+//!
+//! // In on_match_comm:
+//! if let Some(received) = comms.receive::<TeamIntent>(match_comm) {
+//!     self.intents.insert(received.sender_index, received.message);
+//! }
+//! ```
+
+use rlbot_flat::flat::MatchComm;
+use thiserror::Error;
+
+use crate::util::PacketQueue;
+
+const MAGIC: [u8; 2] = *b"RB";
+const HEADER_LEN: usize = 5;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum CommsError {
+    #[error("Content is not a team message")]
+    NotATeamMessage,
+    #[error("Expected message kind {expected}, found {found}")]
+    WrongKind { expected: u16, found: u16 },
+    #[error("Expected message version {expected}, found {found}")]
+    VersionMismatch { expected: u8, found: u8 },
+    #[error("Malformed message payload")]
+    Malformed,
+}
+
+/// A message that can be sent to teammates through [MatchComm].
+pub trait TeamMessage: Sized {
+    /// Identifies this type of message. Must be unique among the message types
+    /// used by a team.
+    const KIND: u16;
+    /// The version of the encoding. Messages with a different version are
+    /// rejected with [CommsError::VersionMismatch].
+    const VERSION: u8;
+
+    fn encode(&self, buf: &mut Vec<u8>);
+    fn decode(payload: &[u8]) -> Result<Self, CommsError>;
+
+    /// Human-readable text shown in-game, if any.
+    fn display(&self) -> Option<String> {
+        None
+    }
+}
+
+/// A decoded [TeamMessage] along with information about its sender.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Received<T> {
+    pub sender_index: u32,
+    pub team: u32,
+    pub team_only: bool,
+    pub message: T,
+}
+
+/// Encode a message, including the header, into [MatchComm::content].
+#[must_use]
+pub fn encode<T: TeamMessage>(message: &T) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(16);
+    buf.extend_from_slice(&MAGIC);
+    buf.extend_from_slice(&T::KIND.to_le_bytes());
+    buf.push(T::VERSION);
+    message.encode(&mut buf);
+    buf
+}
+
+/// Decode a message from a [MatchComm], without any filtering.
+pub fn decode<T: TeamMessage>(match_comm: &MatchComm) -> Result<Received<T>, CommsError> {
+    let content = &match_comm.content;
+    if content.len() < HEADER_LEN || content[..2] != MAGIC {
+        return Err(CommsError::NotATeamMessage);
+    }
+
+    let kind = u16::from_le_bytes([content[2], content[3]]);
+    if kind != T::KIND {
+        return Err(CommsError::WrongKind {
+            expected: T::KIND,
+            found: kind,
+        });
+    }
+
+    if content[4] != T::VERSION {
+        return Err(CommsError::VersionMismatch {
+            expected: T::VERSION,
+            found: content[4],
+        });
+    }
+
+    Ok(Received {
+        sender_index: match_comm.index,
+        team: match_comm.team,
+        team_only: match_comm.team_only,
+        message: T::decode(&content[HEADER_LEN..])?,
+    })
+}
+
+/// Sends and receives [TeamMessage]s on behalf of a single car.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TeamComms {
+    pub index: u32,
+    pub team: u32,
+    /// Whether [TeamComms::receive] ignores messages sent by the other team.
+    /// Defaults to true.
+    pub team_only: bool,
+}
+
+impl TeamComms {
+    #[must_use]
+    pub fn new(index: u32, team: u32) -> Self {
+        Self {
+            index,
+            team,
+            team_only: true,
+        }
+    }
+
+    /// Set [TeamComms::team_only]. Turn it off to also receive messages from
+    /// opponents that don't send their messages team-only.
+    #[must_use]
+    pub fn with_team_only(mut self, team_only: bool) -> Self {
+        self.team_only = team_only;
+        self
+    }
+
+    /// Build a [MatchComm] containing the given message.
+    #[must_use]
+    pub fn to_match_comm<T: TeamMessage>(&self, message: &T, team_only: bool) -> MatchComm {
+        MatchComm {
+            index: self.index,
+            team: self.team,
+            team_only,
+            display: message.display().unwrap_or_default(),
+            content: encode(message),
+        }
+    }
+
+    /// Queue a [MatchComm] containing the given message.
+    pub fn send<T: TeamMessage>(
+        &self,
+        message: &T,
+        team_only: bool,
+        packet_queue: &mut PacketQueue,
+    ) {
+        packet_queue.push(self.to_match_comm(message, team_only));
+    }
+
+    /// Decode a message sent by another car. Returns `None` for messages
+    /// sent by this car, by the other team if [TeamComms::team_only] is set,
+    /// or that aren't a `T`.
+    #[must_use]
+    pub fn receive<T: TeamMessage>(&self, match_comm: &MatchComm) -> Option<Received<T>> {
+        if match_comm.index == self.index || (self.team_only && match_comm.team != self.team) {
+            return None;
+        }
+        decode(match_comm).ok()
+    }
+}
+
+/// What a car intends to do during a kickoff.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KickoffIntent {
+    Going,
+    Cheating,
+    StayingBack,
+}
+
+/// A general-purpose message for coordinating rotations with teammates.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TeamIntent {
+    /// Going for the ball, expecting to reach it in `eta` seconds.
+    GoingForBall {
+        eta: f32,
+    },
+    /// Following up behind the car going for the ball.
+    Supporting,
+    /// Rotating back towards the own goal.
+    RotatingBack,
+    /// Defending the own goal.
+    Defending,
+    Kickoff(KickoffIntent),
+}
+
+impl TeamMessage for TeamIntent {
+    const KIND: u16 = 0;
+    const VERSION: u8 = 1;
+
+    fn encode(&self, buf: &mut Vec<u8>) {
+        match self {
+            Self::GoingForBall { eta } => {
+                buf.push(0);
+                buf.extend_from_slice(&eta.to_le_bytes());
+            }
+            Self::Supporting => buf.push(1),
+            Self::RotatingBack => buf.push(2),
+            Self::Defending => buf.push(3),
+            Self::Kickoff(intent) => buf.extend_from_slice(&[4, *intent as u8]),
+        }
+    }
+
+    fn decode(payload: &[u8]) -> Result<Self, CommsError> {
+        match payload {
+            [0, eta @ ..] => Ok(Self::GoingForBall {
+                eta: f32::from_le_bytes(eta.try_into().map_err(|_| CommsError::Malformed)?),
+            }),
+            [1] => Ok(Self::Supporting),
+            [2] => Ok(Self::RotatingBack),
+            [3] => Ok(Self::Defending),
+            [4, 0] => Ok(Self::Kickoff(KickoffIntent::Going)),
+            [4, 1] => Ok(Self::Kickoff(KickoffIntent::Cheating)),
+            [4, 2] => Ok(Self::Kickoff(KickoffIntent::StayingBack)),
+            _ => Err(CommsError::Malformed),
+        }
+    }
+}
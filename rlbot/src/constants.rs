@@ -0,0 +1,165 @@
+//! This module contains [PhysicsConstants], the physics values that mutators
+//! can change, derived from the [MatchConfiguration] and kept up to date from
+//! every [GamePacket].
+//!
+//! Example:
+//! ```ignore
+//! use rlbot::constants::PhysicsConstants;
+//!
+//! let mut constants = PhysicsConstants::from_match_configuration(&match_configuration);
+//!
+//! // In tick:
+//! constants.update(&game_packet);
+//! let time = car.time_to_reach_with(ball_location, &constants);
+//! ```
+
+use rlbot_flat::flat::{
+    BallBouncinessMutator, BallMaxSpeedMutator, BallSizeMutator, BoostAmountMutator,
+    BoostStrengthMutator, CollisionShape, GamePacket, GameSpeedMutator, GravityMutator,
+    MatchConfiguration, MutatorSettings,
+};
+
+/// The collision radius of the standard soccar ball.
+pub const BALL_RADIUS: f32 = 92.75;
+/// The maximum speed of a car.
+pub const MAX_CAR_SPEED: f32 = 2300.;
+/// The height of a car resting on the ground.
+pub const CAR_REST_HEIGHT: f32 = 17.01;
+/// Gravity without mutators.
+pub const DEFAULT_GRAVITY_Z: f32 = -650.;
+/// The acceleration boosting adds on top of throttle, without mutators.
+pub const DEFAULT_BOOST_ACCELERATION: f32 = 991.667;
+/// The maximum speed of the ball without mutators.
+pub const DEFAULT_BALL_MAX_SPEED: f32 = 6000.;
+/// The ratio of speed the ball keeps perpendicular to a surface it bounces
+/// off, without mutators.
+pub const DEFAULT_BALL_RESTITUTION: f32 = 0.6;
+
+/// Physics values affected by mutators.
+///
+/// Values for mutators that aren't sent by core are approximations. Gravity,
+/// game speed, and ball radius are replaced by the real values on every
+/// [PhysicsConstants::update].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PhysicsConstants {
+    pub gravity_z: f32,
+    /// How fast the game runs compared to real time.
+    pub game_speed: f32,
+    pub ball_radius: f32,
+    pub ball_max_speed: f32,
+    pub ball_restitution: f32,
+    pub car_max_speed: f32,
+    pub boost_acceleration: f32,
+    /// Whether cars can boost at all.
+    pub boost_available: bool,
+    pub unlimited_boost: bool,
+}
+
+impl Default for PhysicsConstants {
+    fn default() -> Self {
+        Self {
+            gravity_z: DEFAULT_GRAVITY_Z,
+            game_speed: 1.,
+            ball_radius: BALL_RADIUS,
+            ball_max_speed: DEFAULT_BALL_MAX_SPEED,
+            ball_restitution: DEFAULT_BALL_RESTITUTION,
+            car_max_speed: MAX_CAR_SPEED,
+            boost_acceleration: DEFAULT_BOOST_ACCELERATION,
+            boost_available: true,
+            unlimited_boost: false,
+        }
+    }
+}
+
+impl PhysicsConstants {
+    #[must_use]
+    pub fn from_match_configuration(match_configuration: &MatchConfiguration) -> Self {
+        match &match_configuration.mutators {
+            Some(mutators) => Self::from_mutators(mutators),
+            None => Self::default(),
+        }
+    }
+
+    #[must_use]
+    pub fn from_mutators(mutators: &MutatorSettings) -> Self {
+        let gravity_z = match mutators.gravity {
+            GravityMutator::Default => DEFAULT_GRAVITY_Z,
+            GravityMutator::Low => DEFAULT_GRAVITY_Z / 2.,
+            GravityMutator::High => DEFAULT_GRAVITY_Z * 1.75,
+            GravityMutator::SuperHigh => DEFAULT_GRAVITY_Z * 5.,
+            GravityMutator::Reverse => -DEFAULT_GRAVITY_Z,
+        };
+
+        let game_speed = match mutators.game_speed {
+            GameSpeedMutator::Default => 1.,
+            GameSpeedMutator::SloMo => 0.5,
+            GameSpeedMutator::TimeWarp => 1.5,
+        };
+
+        let ball_scale = match mutators.ball_size {
+            BallSizeMutator::Default => 1.,
+            BallSizeMutator::Small => 0.5,
+            BallSizeMutator::Medium => 1.5,
+            BallSizeMutator::Large => 2.,
+            BallSizeMutator::Gigantic => 4.,
+        };
+
+        let ball_max_speed = match mutators.ball_max_speed {
+            BallMaxSpeedMutator::Default => DEFAULT_BALL_MAX_SPEED,
+            BallMaxSpeedMutator::Slow => DEFAULT_BALL_MAX_SPEED / 2.,
+            BallMaxSpeedMutator::Fast => DEFAULT_BALL_MAX_SPEED * 1.5,
+            BallMaxSpeedMutator::SuperFast => DEFAULT_BALL_MAX_SPEED * 2.,
+        };
+
+        let bounciness = match mutators.ball_bounciness {
+            BallBouncinessMutator::Default => 1.,
+            BallBouncinessMutator::Low => 0.5,
+            BallBouncinessMutator::Lowish => 0.75,
+            BallBouncinessMutator::High => 1.5,
+            BallBouncinessMutator::SuperHigh => 2.,
+        };
+
+        let boost_strength = match mutators.boost_strength {
+            BoostStrengthMutator::One => 1.,
+            BoostStrengthMutator::OneAndAHalf => 1.5,
+            BoostStrengthMutator::Two => 2.,
+            BoostStrengthMutator::Five => 5.,
+            BoostStrengthMutator::Ten => 10.,
+        };
+
+        Self {
+            gravity_z,
+            game_speed,
+            ball_radius: BALL_RADIUS * ball_scale,
+            ball_max_speed,
+            ball_restitution: DEFAULT_BALL_RESTITUTION * bounciness,
+            car_max_speed: MAX_CAR_SPEED,
+            boost_acceleration: DEFAULT_BOOST_ACCELERATION * boost_strength,
+            boost_available: mutators.boost_amount != BoostAmountMutator::NoBoost,
+            unlimited_boost: mutators.boost_amount == BoostAmountMutator::UnlimitedBoost,
+        }
+    }
+
+    /// Update the values that are sent by core: gravity and game speed from
+    /// [MatchInfo](crate::flat::MatchInfo), and the radius of the first ball.
+    pub fn update(&mut self, game_packet: &GamePacket) {
+        self.gravity_z = game_packet.match_info.world_gravity_z;
+        self.game_speed = game_packet.match_info.game_speed;
+
+        if let Some(ball) = game_packet.balls.first() {
+            self.ball_radius = match &ball.shape {
+                CollisionShape::SphereShape(sphere) => sphere.diameter / 2.,
+                CollisionShape::CylinderShape(cylinder) => cylinder.diameter / 2.,
+                CollisionShape::BoxShape(shape) => shape.length.max(shape.width) / 2.,
+            };
+        }
+    }
+
+    /// Seconds for a ball at rest at `height` to fall to the ground, or
+    /// `None` if gravity doesn't pull it down.
+    #[must_use]
+    pub fn ball_fall_time(&self, height: f32) -> Option<f32> {
+        let fall = height - self.ball_radius;
+        (self.gravity_z < 0.).then(|| (2. * fall.max(0.) / -self.gravity_z).sqrt())
+    }
+}
@@ -0,0 +1,406 @@
+//! This module contains utilities for producing [ControllerState]s: range
+//! validation and clamping, timed [ActionSequence]s for flips and other
+//! multi-tick mechanics, and an [InputFilter] for smoothing analog inputs.
+//!
+//! [Controller] combines all of them and pushes the resulting [PlayerInput].
+//!
+//! Example:
+//! ```ignore
+//! use rlbot::controller::{ActionSequence, Controller};
+//!
+//! let mut controller = Controller::new(index).with_smoothing(0.5);
+//!
+//! // In tick:
+//! if should_flip && !controller.is_busy() {
+//!     controller.start(ActionSequence::front_flip());
+//! }
+//!
+//! let fallback = ControllerState { throttle: 1., steer, ..Default::default() };
+//! controller.tick(game_packet.match_info.seconds_elapsed, fallback, packet_queue);
+//! ```
+
+use rlbot_flat::flat::{ControllerState, PlayerInput};
+
+use crate::util::PacketQueue;
+
+pub trait ControllerStateExt {
+    fn is_valid(&self) -> bool;
+
+    fn clamp(&mut self);
+
+    #[must_use]
+    fn clamped(self) -> Self;
+}
+
+impl ControllerStateExt for ControllerState {
+    /// Whether all analog inputs are finite and within `[-1, 1]`.
+    fn is_valid(&self) -> bool {
+        analog_axes(self)
+            .iter()
+            .all(|axis| axis.is_finite() && (-1.0..=1.).contains(axis))
+    }
+
+    /// Clamp all analog inputs to `[-1, 1]`. Inputs that aren't finite are
+    /// set to 0.
+    fn clamp(&mut self) {
+        for axis in analog_axes_mut(self) {
+            *axis = if axis.is_finite() {
+                axis.clamp(-1., 1.)
+            } else {
+                0.
+            };
+        }
+    }
+
+    fn clamped(mut self) -> Self {
+        self.clamp();
+        self
+    }
+}
+
+fn analog_axes(state: &ControllerState) -> [f32; 5] {
+    [
+        state.throttle,
+        state.steer,
+        state.pitch,
+        state.yaw,
+        state.roll,
+    ]
+}
+
+fn analog_axes_mut(state: &mut ControllerState) -> [&mut f32; 5] {
+    [
+        &mut state.throttle,
+        &mut state.steer,
+        &mut state.pitch,
+        &mut state.yaw,
+        &mut state.roll,
+    ]
+}
+
+/// How long a step of an [ActionSequence] is held.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StepLength {
+    /// A number of calls to [ActionSequence::tick].
+    Ticks(u32),
+    /// Game seconds.
+    Seconds(f32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ActionStep {
+    pub controls: ControllerState,
+    pub length: StepLength,
+}
+
+/// A list of inputs, each held for a given length.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ActionSequence {
+    steps: Vec<ActionStep>,
+    current: usize,
+    ticks_in_step: u32,
+    step_start: Option<f32>,
+}
+
+impl ActionSequence {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a step.
+    #[must_use]
+    pub fn then(mut self, controls: ControllerState, length: StepLength) -> Self {
+        self.steps.push(ActionStep { controls, length });
+        self
+    }
+
+    /// Append a step held for a number of ticks.
+    #[must_use]
+    pub fn then_ticks(self, controls: ControllerState, ticks: u32) -> Self {
+        self.then(controls, StepLength::Ticks(ticks))
+    }
+
+    /// Append a step held for a number of game seconds.
+    #[must_use]
+    pub fn then_seconds(self, controls: ControllerState, seconds: f32) -> Self {
+        self.then(controls, StepLength::Seconds(seconds))
+    }
+
+    /// A jump followed by a dodge in the given direction, where negative
+    /// `pitch` is forwards and positive `yaw` is to the right.
+    #[must_use]
+    pub fn flip(pitch: f32, yaw: f32) -> Self {
+        Self::new()
+            .then_seconds(
+                ControllerState {
+                    jump: true,
+                    ..Default::default()
+                },
+                0.1,
+            )
+            .then_ticks(ControllerState::default(), 2)
+            .then_seconds(
+                ControllerState {
+                    jump: true,
+                    pitch,
+                    yaw,
+                    ..Default::default()
+                },
+                0.05,
+            )
+            .then_seconds(ControllerState::default(), 0.6)
+    }
+
+    #[must_use]
+    pub fn front_flip() -> Self {
+        Self::flip(-1., 0.)
+    }
+
+    /// A backflip that's cancelled halfway and rolled upright, leaving the
+    /// car driving in the opposite direction.
+    #[must_use]
+    pub fn half_flip() -> Self {
+        Self::new()
+            .then_seconds(
+                ControllerState {
+                    jump: true,
+                    ..Default::default()
+                },
+                0.1,
+            )
+            .then_ticks(ControllerState::default(), 2)
+            .then_seconds(
+                ControllerState {
+                    jump: true,
+                    pitch: 1.,
+                    ..Default::default()
+                },
+                0.05,
+            )
+            .then_seconds(
+                ControllerState {
+                    pitch: -1.,
+                    ..Default::default()
+                },
+                0.25,
+            )
+            .then_seconds(
+                ControllerState {
+                    pitch: -1.,
+                    roll: 1.,
+                    throttle: 1.,
+                    ..Default::default()
+                },
+                0.4,
+            )
+    }
+
+    /// A short jump with the nose tilted up, followed by a forward dodge just
+    /// before landing. Meant to be started while driving on flat ground.
+    #[must_use]
+    pub fn wavedash() -> Self {
+        Self::new()
+            .then_seconds(
+                ControllerState {
+                    jump: true,
+                    ..Default::default()
+                },
+                0.05,
+            )
+            .then_seconds(
+                ControllerState {
+                    pitch: 0.3,
+                    ..Default::default()
+                },
+                0.45,
+            )
+            .then_seconds(
+                ControllerState {
+                    jump: true,
+                    pitch: -1.,
+                    ..Default::default()
+                },
+                0.05,
+            )
+            .then_seconds(
+                ControllerState {
+                    throttle: 1.,
+                    ..Default::default()
+                },
+                0.2,
+            )
+    }
+
+    #[must_use]
+    pub fn steps(&self) -> &[ActionStep] {
+        &self.steps
+    }
+
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.current >= self.steps.len()
+    }
+
+    /// Start the sequence over from the first step.
+    pub fn reset(&mut self) {
+        self.current = 0;
+        self.ticks_in_step = 0;
+        self.step_start = None;
+    }
+
+    /// Advance the sequence. Returns the inputs for this tick, or `None` once
+    /// every step has been held for its full length.
+    pub fn tick(&mut self, game_seconds: f32) -> Option<ControllerState> {
+        while let Some(step) = self.steps.get(self.current) {
+            let step_start = *self.step_start.get_or_insert(game_seconds);
+            let done = match step.length {
+                StepLength::Ticks(ticks) => self.ticks_in_step >= ticks,
+                StepLength::Seconds(seconds) => game_seconds - step_start >= seconds,
+            };
+
+            if !done {
+                self.ticks_in_step += 1;
+                return Some(step.controls);
+            }
+
+            self.current += 1;
+            self.ticks_in_step = 0;
+            self.step_start = None;
+        }
+
+        None
+    }
+}
+
+/// Smooths analog inputs over time. Buttons are passed through unchanged.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct InputFilter {
+    /// How much of the previous input is kept each tick, from 0 (no
+    /// smoothing) to 1 (the input never changes).
+    pub smoothing: f32,
+    /// Analog inputs with an absolute value below this are set to 0.
+    pub deadzone: f32,
+    previous: Option<ControllerState>,
+}
+
+impl InputFilter {
+    #[must_use]
+    pub fn new(smoothing: f32, deadzone: f32) -> Self {
+        Self {
+            smoothing: smoothing.clamp(0., 1.),
+            deadzone,
+            previous: None,
+        }
+    }
+
+    /// Forget the previous input, so the next one is used as is.
+    pub fn reset(&mut self) {
+        self.previous = None;
+    }
+
+    pub fn apply(&mut self, target: ControllerState) -> ControllerState {
+        let mut output = target.clamped();
+
+        if let Some(previous) = self.previous {
+            for (axis, previous) in analog_axes_mut(&mut output)
+                .into_iter()
+                .zip(analog_axes(&previous))
+            {
+                *axis = previous + (*axis - previous) * (1. - self.smoothing);
+            }
+        }
+
+        for axis in analog_axes_mut(&mut output) {
+            if axis.abs() < self.deadzone {
+                *axis = 0.;
+            }
+        }
+
+        self.previous = Some(output);
+        output
+    }
+}
+
+/// Produces the [PlayerInput] of a single car. A running [ActionSequence]
+/// takes priority over the inputs given to [Controller::tick], and isn't
+/// smoothed, so its timing stays exact.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Controller {
+    player_index: u32,
+    filter: InputFilter,
+    sequence: Option<ActionSequence>,
+}
+
+impl Controller {
+    #[must_use]
+    pub fn new(player_index: u32) -> Self {
+        Self {
+            player_index,
+            filter: InputFilter::default(),
+            sequence: None,
+        }
+    }
+
+    /// See [InputFilter::smoothing].
+    #[must_use]
+    pub fn with_smoothing(mut self, smoothing: f32) -> Self {
+        self.filter.smoothing = smoothing.clamp(0., 1.);
+        self
+    }
+
+    /// See [InputFilter::deadzone].
+    #[must_use]
+    pub fn with_deadzone(mut self, deadzone: f32) -> Self {
+        self.filter.deadzone = deadzone;
+        self
+    }
+
+    /// Start a sequence, replacing any running one.
+    pub fn start(&mut self, sequence: ActionSequence) {
+        self.sequence = Some(sequence);
+    }
+
+    /// Stop the running sequence, if any.
+    pub fn cancel(&mut self) {
+        self.sequence = None;
+    }
+
+    /// Whether a sequence is running.
+    #[must_use]
+    pub fn is_busy(&self) -> bool {
+        self.sequence.is_some()
+    }
+
+    /// Push the inputs for this tick and return them. `fallback` is used when
+    /// no sequence is running.
+    pub fn tick(
+        &mut self,
+        game_seconds: f32,
+        fallback: ControllerState,
+        packet_queue: &mut PacketQueue,
+    ) -> ControllerState {
+        let sequence_controls = self
+            .sequence
+            .as_mut()
+            .and_then(|sequence| sequence.tick(game_seconds));
+
+        let controller_state = match sequence_controls {
+            Some(controls) => {
+                self.filter.reset();
+                controls.clamped()
+            }
+            None => {
+                self.sequence = None;
+                self.filter.apply(fallback)
+            }
+        };
+
+        packet_queue.push(PlayerInput {
+            player_index: self.player_index,
+            controller_state,
+        });
+
+        controller_state
+    }
+}
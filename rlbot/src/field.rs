@@ -0,0 +1,219 @@
+//! This module contains a typed model of the arena, built from [FieldInfo].
+//!
+//! Example:
+//! ```ignore
+//! use rlbot::field::{BoostPadKind, Field};
+//!
+//! let field = Field::new(&field_info);
+//!
+//! let target = field.opponent_goal(team).unwrap().location;
+//! let pad = field.nearest_pad(car_location, Some(BoostPadKind::Big));
+//! ```
+
+use rlbot_flat::{
+    flat::{Color, FieldInfo},
+    glam::Vec3,
+};
+
+use crate::render::Renderer;
+
+/// Distance from the center of the standard soccar arena to the side walls.
+pub const SIDE_WALL_X: f32 = 4096.;
+/// Distance from the center of the standard soccar arena to the back walls.
+pub const BACK_WALL_Y: f32 = 5120.;
+/// Height of the ceiling in the standard soccar arena.
+pub const CEILING_Z: f32 = 2044.;
+/// The corner walls of the standard soccar arena lie where `|x| + |y|` equals
+/// this value.
+pub const CORNER_WALL_XY: f32 = 8064.;
+/// Half of the width of a standard soccar goal.
+pub const GOAL_HALF_WIDTH: f32 = 892.755;
+/// Height of a standard soccar goal.
+pub const GOAL_HEIGHT: f32 = 642.775;
+/// Depth of a standard soccar goal, measured from the back wall.
+pub const GOAL_DEPTH: f32 = 880.;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BoostPadKind {
+    /// A big pad, giving 100 boost.
+    Big,
+    /// A small pad, giving 12 boost.
+    Small,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoostPad {
+    /// The index of this pad in [FieldInfo::boost_pads] and
+    /// [GamePacket::boost_pads](crate::flat::GamePacket::boost_pads).
+    pub index: usize,
+    pub location: Vec3,
+    pub kind: BoostPadKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Goal {
+    /// The team defending this goal.
+    pub team: u32,
+    /// The center of the goal mouth.
+    pub location: Vec3,
+    /// The direction the goal is facing, i.e. pointing into the field.
+    pub direction: Vec3,
+    pub width: f32,
+    pub height: f32,
+}
+
+/// A typed model of the arena. Goals and boost pads come from [FieldInfo],
+/// but the walls used by [Field::contains] and [Field::distance_to_wall] are
+/// always those of the standard soccar arena.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Field {
+    pub goals: Vec<Goal>,
+    pub boost_pads: Vec<BoostPad>,
+}
+
+impl Field {
+    #[must_use]
+    pub fn new(field_info: &FieldInfo) -> Self {
+        let goals = field_info
+            .goals
+            .iter()
+            .map(|goal| Goal {
+                team: goal.team_num,
+                location: goal.location.into(),
+                direction: Vec3::from(goal.direction).normalize_or_zero(),
+                width: goal.width,
+                height: goal.height,
+            })
+            .collect();
+
+        let boost_pads = field_info
+            .boost_pads
+            .iter()
+            .enumerate()
+            .map(|(index, pad)| BoostPad {
+                index,
+                location: pad.location.into(),
+                kind: if pad.is_full_boost {
+                    BoostPadKind::Big
+                } else {
+                    BoostPadKind::Small
+                },
+            })
+            .collect();
+
+        Self { goals, boost_pads }
+    }
+
+    /// The goal defended by the given team.
+    #[must_use]
+    pub fn own_goal(&self, team: u32) -> Option<&Goal> {
+        self.goals.iter().find(|goal| goal.team == team)
+    }
+
+    /// The goal the given team is attacking.
+    #[must_use]
+    pub fn opponent_goal(&self, team: u32) -> Option<&Goal> {
+        self.goals.iter().find(|goal| goal.team != team)
+    }
+
+    pub fn big_pads(&self) -> impl Iterator<Item = &BoostPad> {
+        self.boost_pads
+            .iter()
+            .filter(|pad| pad.kind == BoostPadKind::Big)
+    }
+
+    pub fn small_pads(&self) -> impl Iterator<Item = &BoostPad> {
+        self.boost_pads
+            .iter()
+            .filter(|pad| pad.kind == BoostPadKind::Small)
+    }
+
+    /// The pad nearest to `point`, optionally only considering pads of the
+    /// given kind.
+    #[must_use]
+    pub fn nearest_pad(
+        &self,
+        point: impl Into<Vec3>,
+        kind: Option<BoostPadKind>,
+    ) -> Option<&BoostPad> {
+        let point = point.into();
+        self.boost_pads
+            .iter()
+            .filter(|pad| kind.is_none_or(|kind| pad.kind == kind))
+            .min_by(|a, b| {
+                a.location
+                    .distance_squared(point)
+                    .total_cmp(&b.location.distance_squared(point))
+            })
+    }
+
+    /// Whether `point` is inside the standard soccar arena, including the
+    /// goals. Rounded edges between the floor and walls are ignored.
+    #[must_use]
+    pub fn contains(&self, point: impl Into<Vec3>) -> bool {
+        let point = point.into();
+        if point.z < 0. || point.z > CEILING_Z {
+            return false;
+        }
+
+        let in_goal = point.x.abs() <= GOAL_HALF_WIDTH
+            && point.y.abs() <= BACK_WALL_Y + GOAL_DEPTH
+            && point.z <= GOAL_HEIGHT;
+
+        in_goal || self.distance_to_wall(point) >= 0.
+    }
+
+    /// The horizontal distance from `point` to the closest side, back, or
+    /// corner wall of the standard soccar arena. Negative if `point` is
+    /// outside of the walls.
+    #[must_use]
+    pub fn distance_to_wall(&self, point: impl Into<Vec3>) -> f32 {
+        let point = point.into();
+        let (x, y) = (point.x.abs(), point.y.abs());
+
+        (SIDE_WALL_X - x)
+            .min(BACK_WALL_Y - y)
+            .min((CORNER_WALL_XY - x - y) / std::f32::consts::SQRT_2)
+    }
+}
+
+impl Renderer {
+    /// Draws the outline of the standard soccar arena floor, along with the
+    /// goal mouths of the given field.
+    pub fn field_outline(&mut self, field: &Field, color: Color) {
+        let corner_x = CORNER_WALL_XY - BACK_WALL_Y;
+        let corner_y = CORNER_WALL_XY - SIDE_WALL_X;
+
+        self.polyline_3d(
+            [
+                (corner_x, BACK_WALL_Y),
+                (SIDE_WALL_X, corner_y),
+                (SIDE_WALL_X, -corner_y),
+                (corner_x, -BACK_WALL_Y),
+                (-corner_x, -BACK_WALL_Y),
+                (-SIDE_WALL_X, -corner_y),
+                (-SIDE_WALL_X, corner_y),
+                (-corner_x, BACK_WALL_Y),
+                (corner_x, BACK_WALL_Y),
+            ]
+            .map(|(x, y)| Vec3::new(x, y, 0.)),
+            color,
+        );
+
+        for goal in &field.goals {
+            let lateral = goal.direction.cross(Vec3::Z).normalize_or_zero() * goal.width / 2.;
+            let up = Vec3::Z * goal.height / 2.;
+
+            self.polyline_3d(
+                [
+                    goal.location - lateral - up,
+                    goal.location - lateral + up,
+                    goal.location + lateral + up,
+                    goal.location + lateral - up,
+                    goal.location - lateral - up,
+                ],
+                color,
+            );
+        }
+    }
+}
@@ -0,0 +1,284 @@
+//! This module contains [PacketInterpolator], which estimates the state of the
+//! game at any time around the latest packets, for example when rendering at a
+//! different rate than packets arrive, or when packets were dropped.
+//!
+//! Between two packets, physics are interpolated, with rotations slerped.
+//! After the latest packet, physics are extrapolated for a short time.
+//! Teleports, such as respawns after demolitions, kickoff resets, replays,
+//! and state setting, are detected, so no blending happens across them.
+//!
+//! Example:
+//! ```ignore
+//! use rlbot::interpolation::PacketInterpolator;
+//!
+//! let mut interpolator = PacketInterpolator::new();
+//!
+//! // In tick:
+//! interpolator.update(&game_packet);
+//!
+//! // When rendering:
+//! let estimated = interpolator.state_at(render_seconds).unwrap();
+//! ```
+//!
+//! Interpolating a ball:
+//! ```rust
+//! use rlbot::flat::{BallInfo, CollisionShape, GamePacket, MatchInfo, Physics, SphereShape, Vector3};
+//! use rlbot::interpolation::{PacketInterpolator, extrapolate};
+//!
+//! let packet = |seconds_elapsed: f32, x: f32| GamePacket {
+//!     balls: vec![BallInfo {
+//!         physics: Physics {
+//!             location: Vector3 { x, y: 0., z: 93. },
+//!             velocity: Vector3 { x: 1200., y: 0., z: 0. },
+//!             ..Default::default()
+//!         },
+//!         ..Default::default()
+//!     }],
+//!     match_info: Box::new(MatchInfo { seconds_elapsed, ..Default::default() }),
+//!     ..Default::default()
+//! };
+//!
+//! let mut interpolator = PacketInterpolator::new();
+//! interpolator.update(&packet(10., 0.));
+//! interpolator.update(&packet(10.1, 120.));
+//!
+//! let between = interpolator.state_at(10.05).unwrap();
+//! assert!((between.balls[0].physics.location.x - 60.).abs() < 0.01);
+//!
+//! // The ball was teleported, so it isn't blended
+//! interpolator.update(&packet(10.2, 3000.));
+//! let between = interpolator.state_at(10.15).unwrap();
+//! assert_eq!(between.balls[0].physics.location.x, 120.);
+//!
+//! // A ball resting on the floor doesn't fall through it
+//! let mut resting = packet(10.2, 0.);
+//! resting.balls[0].shape = CollisionShape::SphereShape(Box::new(SphereShape { diameter: 185.5 }));
+//! resting.balls[0].physics.location.z = 92.75;
+//! resting.match_info.world_gravity_z = -650.;
+//! let later = extrapolate(&resting, 0.1);
+//! assert_eq!(later.balls[0].physics.location.z, 92.75);
+//! assert_eq!(later.balls[0].physics.velocity.z, 0.);
+//! ```
+
+use rlbot_flat::{
+    flat::{AirState, GamePacket, MatchPhase, Physics},
+    glam::{Quat, Vec3},
+};
+
+use crate::modes::BallShape;
+
+/// How much further than its velocity explains an object has to move
+/// between two packets to be considered teleported.
+const TELEPORT_MARGIN: f32 = 150.;
+
+/// Whether the object moved further between the two states than its
+/// velocity explains over `dt` seconds.
+#[must_use]
+pub fn is_teleport(before: &Physics, after: &Physics, dt: f32) -> bool {
+    let speed = Vec3::from(before.velocity)
+        .length()
+        .max(Vec3::from(after.velocity).length());
+    let distance = Vec3::from(before.location).distance(after.location.into());
+    distance > speed * dt.max(0.) + TELEPORT_MARGIN
+}
+
+/// Whether the change of phase moves objects without physics, like the reset
+/// before a kickoff or a replay.
+fn resets_positions(before: MatchPhase, after: MatchPhase) -> bool {
+    before != after
+        && (matches!(
+            after,
+            MatchPhase::Countdown | MatchPhase::Kickoff | MatchPhase::Replay
+        ) || before == MatchPhase::Replay)
+}
+
+/// Interpolate physics, with `t` from 0 at `before` to 1 at `after`.
+#[must_use]
+pub fn lerp_physics(before: &Physics, after: &Physics, t: f32) -> Physics {
+    let lerp = |a: Vec3, b: Vec3| a.lerp(b, t).into();
+    Physics {
+        location: lerp(before.location.into(), after.location.into()),
+        rotation: Quat::from(before.rotation)
+            .slerp(after.rotation.into(), t)
+            .into(),
+        velocity: lerp(before.velocity.into(), after.velocity.into()),
+        angular_velocity: lerp(
+            before.angular_velocity.into(),
+            after.angular_velocity.into(),
+        ),
+    }
+}
+
+/// Extrapolate physics by `dt` seconds, with `gravity_z` applied when
+/// `airborne`.
+#[must_use]
+pub fn extrapolate_physics(physics: &Physics, dt: f32, gravity_z: f32, airborne: bool) -> Physics {
+    let gravity = if airborne {
+        Vec3::new(0., 0., gravity_z)
+    } else {
+        Vec3::ZERO
+    };
+    let location = Vec3::from(physics.location);
+    let velocity = Vec3::from(physics.velocity);
+    let angular_velocity = Vec3::from(physics.angular_velocity);
+
+    Physics {
+        location: (location + velocity * dt + gravity * dt * dt / 2.).into(),
+        rotation: (Quat::from_scaled_axis(angular_velocity * dt) * Quat::from(physics.rotation))
+            .normalize()
+            .into(),
+        velocity: (velocity + gravity * dt).into(),
+        angular_velocity: physics.angular_velocity,
+    }
+}
+
+/// Interpolate between two packets at `seconds`, keyed by
+/// [MatchInfo::seconds_elapsed](crate::flat::MatchInfo::seconds_elapsed).
+///
+/// Objects that teleported keep their state from `before` until `after`.
+/// If the players or balls changed, or the match phase reset positions,
+/// nothing is blended. Everything but physics and the elapsed time is taken
+/// from `after`.
+#[must_use]
+pub fn interpolate(before: &GamePacket, after: &GamePacket, seconds: f32) -> GamePacket {
+    let start = before.match_info.seconds_elapsed;
+    let end = after.match_info.seconds_elapsed;
+    let dt = end - start;
+
+    let blendable = dt > 0.
+        && before.players.len() == after.players.len()
+        && before.balls.len() == after.balls.len()
+        && !resets_positions(before.match_info.match_phase, after.match_info.match_phase);
+    if !blendable {
+        return if seconds < end {
+            before.clone()
+        } else {
+            after.clone()
+        };
+    }
+
+    let t = ((seconds - start) / dt).clamp(0., 1.);
+    let blend = |a: &Physics, b: &Physics, teleported: bool| {
+        if t >= 1. {
+            *b
+        } else if teleported || is_teleport(a, b, dt) {
+            *a
+        } else {
+            lerp_physics(a, b, t)
+        }
+    };
+
+    let mut game_packet = after.clone();
+    game_packet.match_info.seconds_elapsed = start + dt * t;
+
+    for (player, previous) in game_packet.players.iter_mut().zip(&before.players) {
+        let respawned = (previous.demolished_timeout > 0.) != (player.demolished_timeout > 0.);
+        player.physics = blend(&previous.physics, &player.physics, respawned);
+    }
+
+    for (ball, previous) in game_packet.balls.iter_mut().zip(&before.balls) {
+        ball.physics = blend(&previous.physics, &ball.physics, false);
+    }
+
+    game_packet
+}
+
+/// Extrapolate a packet by `dt` seconds. Balls and airborne cars fall with
+/// the gravity of the match, but balls stop at the floor. Demolished cars
+/// stay in place. Nothing moves while the game is paused.
+#[must_use]
+pub fn extrapolate(game_packet: &GamePacket, dt: f32) -> GamePacket {
+    let mut game_packet = game_packet.clone();
+    if game_packet.match_info.match_phase == MatchPhase::Paused {
+        return game_packet;
+    }
+
+    let gravity_z = game_packet.match_info.world_gravity_z;
+    game_packet.match_info.seconds_elapsed += dt;
+
+    for player in &mut game_packet.players {
+        if player.demolished_timeout <= 0. {
+            let airborne = player.air_state != AirState::OnGround;
+            player.physics = extrapolate_physics(&player.physics, dt, gravity_z, airborne);
+        }
+    }
+
+    for ball in &mut game_packet.balls {
+        // Balls stop at the floor, but aren't lifted if they were already below it
+        let floor_z = BallShape::of(ball)
+            .rest_height()
+            .min(ball.physics.location.z);
+        ball.physics = extrapolate_physics(&ball.physics, dt, gravity_z, true);
+
+        if ball.physics.location.z < floor_z {
+            ball.physics.location.z = floor_z;
+            ball.physics.velocity.z = ball.physics.velocity.z.max(0.);
+        }
+    }
+
+    game_packet
+}
+
+/// Keeps the latest two packets to estimate the state of the game at any
+/// time, see [interpolate] and [extrapolate].
+#[derive(Debug, Clone)]
+pub struct PacketInterpolator {
+    previous: Option<GamePacket>,
+    latest: Option<GamePacket>,
+    max_extrapolation: f32,
+}
+
+impl Default for PacketInterpolator {
+    fn default() -> Self {
+        Self {
+            previous: None,
+            latest: None,
+            max_extrapolation: 0.1,
+        }
+    }
+}
+
+impl PacketInterpolator {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limit how far past the latest packet states are extrapolated, in
+    /// seconds. Defaults to 0.1.
+    #[must_use]
+    pub fn with_max_extrapolation(mut self, seconds: f32) -> Self {
+        self.max_extrapolation = seconds.max(0.);
+        self
+    }
+
+    pub fn update(&mut self, game_packet: &GamePacket) {
+        self.previous = self.latest.replace(game_packet.clone());
+    }
+
+    #[must_use]
+    pub fn latest(&self) -> Option<&GamePacket> {
+        self.latest.as_ref()
+    }
+
+    /// The estimated state at `seconds`, or `None` before the first packet.
+    /// Times before the previous packet return the previous packet.
+    #[must_use]
+    pub fn state_at(&self, seconds: f32) -> Option<GamePacket> {
+        let latest = self.latest.as_ref()?;
+        let latest_seconds = latest.match_info.seconds_elapsed;
+
+        if seconds >= latest_seconds {
+            let dt = (seconds - latest_seconds).min(self.max_extrapolation);
+            return Some(extrapolate(latest, dt));
+        }
+
+        Some(match &self.previous {
+            Some(previous) if seconds > previous.match_info.seconds_elapsed => {
+                interpolate(previous, latest, seconds)
+            }
+            Some(previous) => previous.clone(),
+            None => latest.clone(),
+        })
+    }
+}
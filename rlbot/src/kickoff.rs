@@ -0,0 +1,220 @@
+//! This module contains kickoff helpers: a [KickoffDetector] that reports when
+//! kickoffs start and end, [classify_spawn] to find out where a car spawned,
+//! and [assign_roles] to decide who goes for the ball.
+//!
+//! Roles are expressed as [KickoffIntent]s, so they can be shared with
+//! teammates through [TeamComms](crate::comms::TeamComms).
+//!
+//! Example:
+//! ```ignore
+//! use rlbot::kickoff::{KickoffDetector, KickoffEvent, controllable_roles};
+//!
+//! let mut kickoffs = KickoffDetector::new(match_configuration.game_mode);
+//!
+//! // In tick:
+//! if let Some(KickoffEvent::Started { .. }) = kickoffs.update(&game_packet) {
+//!     self.roles = controllable_roles(&game_packet, &controllable_team_info, game_mode);
+//! }
+//! ```
+
+use rlbot_flat::{
+    flat::{ControllableTeamInfo, GameMode, GamePacket, MatchPhase},
+    glam::Vec3,
+};
+
+pub use crate::state_builder::KickoffSpawn;
+use crate::{comms::KickoffIntent, player::PlayerInfoExt};
+
+/// Cars further away than this from a standard spawn aren't classified.
+const SPAWN_TOLERANCE: f32 = 200.;
+
+/// Lateral offset, relative to the distance to the ball, above which a spawn
+/// counts as diagonal in non-standard arenas.
+const DIAGONAL_RATIO: f32 = 0.5;
+/// Lateral offset below which a spawn counts as centered in non-standard
+/// arenas.
+const CENTER_OFFSET: f32 = 50.;
+
+/// Whether the game mode is played in the standard arena with the standard
+/// kickoff spawns.
+#[must_use]
+pub fn has_standard_spawns(game_mode: GameMode) -> bool {
+    matches!(
+        game_mode,
+        GameMode::Soccar | GameMode::Rumble | GameMode::Snowday | GameMode::Heatseeker
+    )
+}
+
+/// Classify the spawn of a car at `location` on the given team.
+///
+/// In the standard arena the location is matched against the known spawns.
+/// In other arenas the spawn is inferred from the position relative to
+/// `ball_location`, so diagonal means "far to the side of the ball" and back
+/// center means "straight behind it".
+#[must_use]
+pub fn classify_spawn(
+    location: Vec3,
+    team: u32,
+    game_mode: GameMode,
+    ball_location: Vec3,
+) -> Option<KickoffSpawn> {
+    if has_standard_spawns(game_mode) {
+        return KickoffSpawn::ALL.into_iter().find(|spawn| {
+            Vec3::from(spawn.location(team))
+                .truncate()
+                .distance(location.truncate())
+                < SPAWN_TOLERANCE
+        });
+    }
+
+    // From the perspective of the team, so positive y is towards the ball
+    // and negative x is to the right.
+    let side = if team == 0 { 1. } else { -1. };
+    let offset = (location - ball_location) * side;
+    let is_right = offset.x < 0.;
+
+    Some(if offset.x.abs() < CENTER_OFFSET {
+        KickoffSpawn::BackCenter
+    } else if offset.x.abs() > -offset.y * DIAGONAL_RATIO {
+        if is_right {
+            KickoffSpawn::DiagonalRight
+        } else {
+            KickoffSpawn::DiagonalLeft
+        }
+    } else if is_right {
+        KickoffSpawn::OffCenterRight
+    } else {
+        KickoffSpawn::OffCenterLeft
+    })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum KickoffEvent {
+    /// A kickoff countdown started. Contains the spawn of every car.
+    Started { spawns: Vec<Option<KickoffSpawn>> },
+    /// The ball was hit, or the kickoff was otherwise interrupted.
+    Ended,
+}
+
+/// Reports when kickoffs start and end, based on [MatchPhase].
+#[derive(Debug, Clone)]
+pub struct KickoffDetector {
+    game_mode: GameMode,
+    in_kickoff: bool,
+}
+
+impl KickoffDetector {
+    #[must_use]
+    pub fn new(game_mode: GameMode) -> Self {
+        Self {
+            game_mode,
+            in_kickoff: false,
+        }
+    }
+
+    /// Whether a kickoff is currently happening.
+    #[must_use]
+    pub fn is_kickoff(&self) -> bool {
+        self.in_kickoff
+    }
+
+    pub fn update(&mut self, game_packet: &GamePacket) -> Option<KickoffEvent> {
+        let in_kickoff = is_kickoff_phase(game_packet.match_info.match_phase);
+        let was_kickoff = self.in_kickoff;
+        self.in_kickoff = in_kickoff;
+
+        match (was_kickoff, in_kickoff) {
+            (false, true) => Some(KickoffEvent::Started {
+                spawns: spawns(game_packet, self.game_mode),
+            }),
+            (true, false) => Some(KickoffEvent::Ended),
+            _ => None,
+        }
+    }
+}
+
+fn is_kickoff_phase(match_phase: MatchPhase) -> bool {
+    matches!(match_phase, MatchPhase::Countdown | MatchPhase::Kickoff)
+}
+
+/// The spawn of every car in the packet.
+#[must_use]
+pub fn spawns(game_packet: &GamePacket, game_mode: GameMode) -> Vec<Option<KickoffSpawn>> {
+    let ball_location = ball_location(game_packet);
+    game_packet
+        .players
+        .iter()
+        .map(|player| classify_spawn(player.location(), player.team, game_mode, ball_location))
+        .collect()
+}
+
+/// Assign a kickoff role to every car on the team, by index.
+///
+/// The car closest to the ball goes, with the right diagonal winning ties as
+/// is conventional. The second closest cheats and everyone else stays back.
+#[must_use]
+pub fn assign_roles(
+    game_packet: &GamePacket,
+    team: u32,
+    game_mode: GameMode,
+) -> Vec<(usize, KickoffIntent)> {
+    let ball_location = ball_location(game_packet);
+
+    let mut cars: Vec<_> = game_packet
+        .players
+        .iter()
+        .enumerate()
+        .filter(|(_, player)| player.team == team && !player.is_demolished())
+        .map(|(i, player)| {
+            let location = player.location();
+            let spawn = classify_spawn(location, team, game_mode, ball_location);
+            let is_left = matches!(
+                spawn,
+                Some(KickoffSpawn::DiagonalLeft | KickoffSpawn::OffCenterLeft)
+            );
+            // Round so that symmetrical spawns compare equal
+            let distance = location.distance(ball_location).round();
+            (i, distance, is_left)
+        })
+        .collect();
+
+    cars.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.2.cmp(&b.2)));
+
+    cars.into_iter()
+        .enumerate()
+        .map(|(rank, (i, _, _))| {
+            let role = match rank {
+                0 => KickoffIntent::Going,
+                1 => KickoffIntent::Cheating,
+                _ => KickoffIntent::StayingBack,
+            };
+            (i, role)
+        })
+        .collect()
+}
+
+/// [assign_roles] for the team, filtered to the cars this agent controls.
+#[must_use]
+pub fn controllable_roles(
+    game_packet: &GamePacket,
+    controllable_team_info: &ControllableTeamInfo,
+    game_mode: GameMode,
+) -> Vec<(u32, KickoffIntent)> {
+    assign_roles(game_packet, controllable_team_info.team, game_mode)
+        .into_iter()
+        .filter_map(|(i, role)| {
+            controllable_team_info
+                .controllables
+                .iter()
+                .find(|controllable| controllable.index as usize == i)
+                .map(|controllable| (controllable.index, role))
+        })
+        .collect()
+}
+
+fn ball_location(game_packet: &GamePacket) -> Vec3 {
+    game_packet
+        .balls
+        .first()
+        .map_or(Vec3::ZERO, |ball| ball.physics.location.into())
+}
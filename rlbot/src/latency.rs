@@ -0,0 +1,253 @@
+//! This module contains [LatencyMonitor], which measures the round-trip
+//! latency to core using [PingRequest]s and [PingResponse]s.
+//!
+//! The agent runners can own a monitor, and pass its stats to every tick in
+//! [TickContext::latency](crate::agents::TickContext::latency):
+//! ```ignore
+//! let latency_monitor = LatencyMonitor::default().with_logging(true);
+//! run_bot_agents::<MyBot>(agent_id, true, true, Some(latency_monitor), connection)?;
+//!
+//! // In tick:
+//! if let Some(stats) = context.latency {
+//!     let mean_ms = stats.mean.as_secs_f64() * 1000.;
+//! }
+//! ```
+//!
+//! A monitor can also be used directly, for example with a raw connection:
+//! ```ignore
+//! use rlbot::latency::LatencyMonitor;
+//!
+//! let mut latency = LatencyMonitor::default().with_logging(true);
+//!
+//! // Every tick:
+//! latency.tick(packet_queue);
+//!
+//! // For every PingResponse:
+//! latency.on_ping_response(ping);
+//! ```
+
+use std::{
+    collections::VecDeque,
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
+
+use rlbot_flat::flat::{Color, PingRequest, PingResponse, TextHAlign, TextVAlign};
+
+use crate::{
+    render::{Renderer, colors},
+    util::{self, PacketQueue},
+};
+
+/// Shared between all monitors, so no two monitors in a process ever use the
+/// same cookie. Every [PingResponse] is still sent to every agent sharing a
+/// connection, so a monitor has to ignore responses it didn't request, see
+/// [LatencyMonitor::on_ping_response].
+static NEXT_COOKIE: AtomicU64 = AtomicU64::new(0);
+/// Set in the cookies of monitors owned by the agent runners, so the runners
+/// can drop all of their responses instead of passing them to agents.
+const RUNNER_COOKIE: u64 = 1 << 63;
+
+/// Whether the cookie was sent by a monitor owned by an agent runner.
+pub(crate) fn is_runner_cookie(cookie: u64) -> bool {
+    cookie & RUNNER_COOKIE != 0
+}
+
+/// Pings that haven't been answered within this time are counted as lost.
+const PING_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Rolling round-trip time statistics.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LatencyStats {
+    pub last: Duration,
+    pub min: Duration,
+    pub max: Duration,
+    pub mean: Duration,
+    /// Standard deviation of the round-trip times.
+    pub jitter: Duration,
+    /// Number of samples these stats are based on.
+    pub samples: usize,
+    /// Number of pings that were never answered.
+    pub lost: usize,
+}
+
+/// Periodically sends [PingRequest]s and keeps rolling statistics of the
+/// round-trip times.
+#[derive(Debug, Clone)]
+pub struct LatencyMonitor {
+    interval: Duration,
+    window: usize,
+    spike_threshold: Duration,
+    log_spikes: bool,
+    runner: bool,
+    last_sent: Option<Instant>,
+    pending: VecDeque<(u64, Instant)>,
+    samples: VecDeque<Duration>,
+    lost: usize,
+}
+
+impl Default for LatencyMonitor {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(1), 30)
+    }
+}
+
+impl LatencyMonitor {
+    /// Create a monitor sending a ping every `interval` and keeping stats over
+    /// the last `window` responses.
+    #[must_use]
+    pub fn new(interval: Duration, window: usize) -> Self {
+        Self {
+            interval,
+            window: window.max(1),
+            spike_threshold: Duration::from_millis(100),
+            log_spikes: false,
+            runner: false,
+            last_sent: None,
+            pending: VecDeque::new(),
+            samples: VecDeque::with_capacity(window),
+            lost: 0,
+        }
+    }
+
+    /// Round-trip times above this threshold count as a spike.
+    #[must_use]
+    pub fn with_spike_threshold(mut self, threshold: Duration) -> Self {
+        self.spike_threshold = threshold;
+        self
+    }
+
+    /// Log a message to stderr whenever a spike is measured.
+    #[must_use]
+    pub fn with_logging(mut self, log_spikes: bool) -> Self {
+        self.log_spikes = log_spikes;
+        self
+    }
+
+    /// Mark the monitor as owned by an agent runner, see [is_runner_cookie].
+    #[must_use]
+    pub(crate) fn for_runner(mut self) -> Self {
+        self.runner = true;
+        self
+    }
+
+    /// Queue a [PingRequest] if the interval has passed since the last one.
+    /// Should be called every tick.
+    pub fn tick(&mut self, packet_queue: &mut PacketQueue) {
+        let now = Instant::now();
+
+        while let Some(&(_, sent)) = self.pending.front() {
+            if now.duration_since(sent) < PING_TIMEOUT {
+                break;
+            }
+            self.pending.pop_front();
+            self.lost += 1;
+        }
+
+        if self
+            .last_sent
+            .is_some_and(|last_sent| now.duration_since(last_sent) < self.interval)
+        {
+            return;
+        }
+
+        let mut cookie = NEXT_COOKIE.fetch_add(1, Ordering::Relaxed);
+        if self.runner {
+            cookie |= RUNNER_COOKIE;
+        }
+        packet_queue.push(PingRequest { cookie });
+        self.pending.push_back((cookie, now));
+        self.last_sent = Some(now);
+    }
+
+    /// Match a [PingResponse] with a sent request. Returns the round-trip time
+    /// if the response belongs to this monitor.
+    pub fn on_ping_response(&mut self, ping: &PingResponse) -> Option<Duration> {
+        let i = self
+            .pending
+            .iter()
+            .position(|&(cookie, _)| cookie == ping.cookie)?;
+        let (_, sent) = self.pending.remove(i)?;
+        let rtt = sent.elapsed();
+
+        if self.samples.len() == self.window {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(rtt);
+
+        if self.log_spikes && rtt > self.spike_threshold {
+            util::log(format_args!(
+                "Latency spike: round-trip time to RLBot was {:.1} ms",
+                rtt.as_secs_f64() * 1000.
+            ));
+        }
+
+        Some(rtt)
+    }
+
+    /// Whether the most recent round-trip time is above the spike threshold.
+    #[must_use]
+    pub fn is_spiking(&self) -> bool {
+        self.samples
+            .back()
+            .is_some_and(|&last| last > self.spike_threshold)
+    }
+
+    /// Statistics over the last responses, or `None` if no response has been
+    /// received yet.
+    #[must_use]
+    pub fn stats(&self) -> Option<LatencyStats> {
+        let &last = self.samples.back()?;
+        let samples = self.samples.len();
+
+        let mean_secs =
+            self.samples.iter().map(Duration::as_secs_f64).sum::<f64>() / samples as f64;
+        let variance = self
+            .samples
+            .iter()
+            .map(|rtt| (rtt.as_secs_f64() - mean_secs).powi(2))
+            .sum::<f64>()
+            / samples as f64;
+
+        Some(LatencyStats {
+            last,
+            min: *self.samples.iter().min()?,
+            max: *self.samples.iter().max()?,
+            mean: Duration::from_secs_f64(mean_secs),
+            jitter: Duration::from_secs_f64(variance.sqrt()),
+            samples,
+            lost: self.lost,
+        })
+    }
+}
+
+impl Renderer {
+    /// Draws a warning in the top left corner of the screen while the
+    /// monitor is measuring a latency spike.
+    pub fn latency_alert(&mut self, monitor: &LatencyMonitor) {
+        if !monitor.is_spiking() {
+            return;
+        }
+        let Some(stats) = monitor.stats() else {
+            return;
+        };
+
+        self.string_2d(
+            format!(
+                "High latency: {:.0} ms (mean {:.0} ms)",
+                stats.last.as_secs_f64() * 1000.,
+                stats.mean.as_secs_f64() * 1000.
+            ),
+            0.01,
+            0.01,
+            1.,
+            colors::RED,
+            Color {
+                a: 160,
+                ..colors::BLACK
+            },
+            TextHAlign::Left,
+            TextVAlign::Top,
+        );
+    }
+}
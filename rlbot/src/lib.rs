@@ -50,10 +50,47 @@ use rlbot_flat::planus::{self, ReadAsRoot};
 use thiserror::Error;
 
 pub mod agents;
+#[cfg(feature = "glam")]
+pub mod ball_prediction;
+#[cfg(feature = "glam")]
+pub mod ball_sim;
+#[cfg(feature = "glam")]
+pub mod balls;
+#[cfg(feature = "glam")]
+pub mod boost_pads;
+#[cfg(feature = "glam")]
+pub mod car_sim;
+pub mod comms;
+pub mod constants;
+pub mod controller;
+#[cfg(feature = "glam")]
+pub mod field;
+#[cfg(feature = "glam")]
+pub mod interpolation;
+#[cfg(feature = "glam")]
+pub mod kickoff;
+pub mod latency;
+pub mod mirror;
+#[cfg(feature = "glam")]
+pub mod modes;
+#[cfg(feature = "glam")]
+pub mod obs;
 mod pkanal;
+#[cfg(feature = "glam")]
+pub mod player;
+#[cfg(feature = "glam")]
+pub mod randomizer;
 pub mod render;
+pub mod scenario;
+pub mod snapshot;
 pub mod state_builder;
+pub mod state_diff;
+#[cfg(feature = "glam")]
+pub mod stats;
+#[cfg(feature = "glam")]
+pub mod touches;
 pub mod util;
+pub mod validation;
 
 #[cfg(feature = "glam")]
 pub use rlbot_flat::glam;
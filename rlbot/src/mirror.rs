@@ -0,0 +1,271 @@
+//! This module contains [Mirror], which converts flat types between the
+//! perspectives of the two teams.
+//!
+//! Mirroring is a 180 degree rotation around the z axis: x and y are negated
+//! and yaw is turned around, which swaps the sides of the field while keeping
+//! the field's orientation rules intact. Mirroring twice returns the original
+//! value, so a bot on orange can mirror every input into a frame where it
+//! attacks towards +y like blue, and mirror its outputs, such as states to
+//! set, back.
+//!
+//! Boost pads are sorted by location, so mirroring reverses their order. This
+//! only maps every pad onto its counterpart on layouts that are symmetric
+//! around the center of the field, like the standard soccar layout. On other
+//! layouts, the pad states of a mirrored [GamePacket] don't line up with the
+//! pads of the original [FieldInfo], only with those of the mirrored one.
+//!
+//! Example:
+//! ```rust
+//! use rlbot::flat::{Physics, Rotator, Vector3};
+//! use rlbot::mirror::Mirror;
+//!
+//! let physics = Physics {
+//!     location: Vector3 { x: 1000., y: -2000., z: 17. },
+//!     rotation: Rotator { pitch: 0.1, yaw: 0.5, roll: -0.2 },
+//!     velocity: Vector3 { x: 500., y: 300., z: 0. },
+//!     ..Default::default()
+//! };
+//!
+//! let mirrored = physics.mirrored();
+//! assert_eq!(mirrored.location, Vector3 { x: -1000., y: 2000., z: 17. });
+//! assert_eq!(mirrored.velocity, Vector3 { x: -500., y: -300., z: 0. });
+//!
+//! let back = mirrored.mirrored();
+//! assert_eq!(back.location, physics.location);
+//! assert_eq!(back.velocity, physics.velocity);
+//! assert!((back.rotation.yaw - physics.rotation.yaw).abs() < 1e-6);
+//! assert_eq!(back.rotation.pitch, physics.rotation.pitch);
+//! ```
+//!
+//! A whole packet round-trips too:
+//! ```rust
+//! use rlbot::flat::{BallInfo, BoostPadState, GamePacket, Physics, PlayerInfo, Vector3};
+//! use rlbot::mirror::Mirror;
+//!
+//! let physics = Physics {
+//!     location: Vector3 { x: 100., y: 200., z: 300. },
+//!     ..Default::default()
+//! };
+//! let game_packet = GamePacket {
+//!     players: vec![PlayerInfo { physics, team: 1, ..Default::default() }],
+//!     balls: vec![BallInfo { physics, ..Default::default() }],
+//!     boost_pads: vec![
+//!         BoostPadState { is_active: true, timer: 0. },
+//!         BoostPadState { is_active: false, timer: 3. },
+//!     ],
+//!     ..Default::default()
+//! };
+//!
+//! let mirrored = game_packet.for_team(1);
+//! assert_eq!(mirrored.balls[0].physics.location.x, -100.);
+//! assert!(!mirrored.boost_pads[0].is_active);
+//! assert_eq!(mirrored.for_team(1), game_packet);
+//! assert_eq!(game_packet.for_team(0), game_packet);
+//! ```
+
+use std::f32::consts::{PI, TAU};
+
+use rlbot_flat::flat::{
+    BallInfo, BallPrediction, BoostPad, DesiredBallState, DesiredCarState, DesiredGameState,
+    DesiredPhysics, FieldInfo, GamePacket, GoalInfo, Physics, PlayerInfo, PredictionSlice, Rotator,
+    RotatorPartial, Touch, Vector3, Vector3Partial,
+};
+
+/// Whether values need to be mirrored for the team to attack towards +y.
+#[must_use]
+pub fn is_mirrored(team: u32) -> bool {
+    team == 1
+}
+
+pub trait Mirror: Clone {
+    /// Mirror in place.
+    fn mirror(&mut self);
+
+    /// A mirrored copy.
+    #[must_use]
+    fn mirrored(&self) -> Self {
+        let mut mirrored = self.clone();
+        mirrored.mirror();
+        mirrored
+    }
+
+    /// A copy in the frame of the team, where it attacks towards +y. Only
+    /// orange is mirrored.
+    #[must_use]
+    fn for_team(&self, team: u32) -> Self {
+        if is_mirrored(team) {
+            self.mirrored()
+        } else {
+            self.clone()
+        }
+    }
+}
+
+impl<T: Mirror> Mirror for Vec<T> {
+    fn mirror(&mut self) {
+        self.iter_mut().for_each(Mirror::mirror);
+    }
+}
+
+impl<T: Mirror> Mirror for Option<T> {
+    fn mirror(&mut self) {
+        if let Some(value) = self {
+            value.mirror();
+        }
+    }
+}
+
+impl<T: Mirror> Mirror for Box<T> {
+    fn mirror(&mut self) {
+        (**self).mirror();
+    }
+}
+
+impl Mirror for Vector3 {
+    fn mirror(&mut self) {
+        self.x = -self.x;
+        self.y = -self.y;
+    }
+}
+
+#[cfg(feature = "glam")]
+impl Mirror for rlbot_flat::glam::Vec3 {
+    fn mirror(&mut self) {
+        self.x = -self.x;
+        self.y = -self.y;
+    }
+}
+
+/// Turn a yaw around, keeping it within `[-π, π)`.
+fn mirror_yaw(yaw: f32) -> f32 {
+    (yaw + TAU).rem_euclid(TAU) - PI
+}
+
+impl Mirror for Rotator {
+    fn mirror(&mut self) {
+        self.yaw = mirror_yaw(self.yaw);
+    }
+}
+
+impl Mirror for Physics {
+    fn mirror(&mut self) {
+        self.location.mirror();
+        self.rotation.mirror();
+        self.velocity.mirror();
+        self.angular_velocity.mirror();
+    }
+}
+
+impl Mirror for Touch {
+    fn mirror(&mut self) {
+        self.location.mirror();
+        self.normal.mirror();
+    }
+}
+
+/// Values relative to the car, like the hitbox offset and dodge direction,
+/// are unchanged.
+impl Mirror for PlayerInfo {
+    fn mirror(&mut self) {
+        self.physics.mirror();
+        self.latest_touch.mirror();
+    }
+}
+
+impl Mirror for BallInfo {
+    fn mirror(&mut self) {
+        self.physics.mirror();
+    }
+}
+
+impl Mirror for GamePacket {
+    /// Reverses the order of the boost pads, which is only correct for
+    /// symmetric layouts like soccar's, see the [module docs](self).
+    fn mirror(&mut self) {
+        self.players.mirror();
+        self.balls.mirror();
+        self.boost_pads.reverse();
+    }
+}
+
+impl Mirror for PredictionSlice {
+    fn mirror(&mut self) {
+        self.physics.mirror();
+    }
+}
+
+impl Mirror for BallPrediction {
+    fn mirror(&mut self) {
+        self.slices.mirror();
+    }
+}
+
+/// Goals keep their team, so the goal of the team stays its own goal.
+impl Mirror for GoalInfo {
+    fn mirror(&mut self) {
+        self.location.mirror();
+        self.direction.mirror();
+    }
+}
+
+impl Mirror for BoostPad {
+    fn mirror(&mut self) {
+        self.location.mirror();
+    }
+}
+
+impl Mirror for FieldInfo {
+    /// Reverses the order of the boost pads, which is only correct for
+    /// symmetric layouts like soccar's, see the [module docs](self).
+    fn mirror(&mut self) {
+        self.boost_pads.mirror();
+        self.boost_pads.reverse();
+        self.goals.mirror();
+    }
+}
+
+/// Every component that's set is mirrored on its own, so partial vectors
+/// stay partial.
+impl Mirror for Vector3Partial {
+    fn mirror(&mut self) {
+        for value in [&mut self.x, &mut self.y].into_iter().flatten() {
+            value.val = -value.val;
+        }
+    }
+}
+
+impl Mirror for RotatorPartial {
+    fn mirror(&mut self) {
+        if let Some(yaw) = &mut self.yaw {
+            yaw.val = mirror_yaw(yaw.val);
+        }
+    }
+}
+
+impl Mirror for DesiredPhysics {
+    fn mirror(&mut self) {
+        self.location.mirror();
+        self.rotation.mirror();
+        self.velocity.mirror();
+        self.angular_velocity.mirror();
+    }
+}
+
+impl Mirror for DesiredBallState {
+    fn mirror(&mut self) {
+        self.physics.mirror();
+    }
+}
+
+impl Mirror for DesiredCarState {
+    fn mirror(&mut self) {
+        self.physics.mirror();
+    }
+}
+
+impl Mirror for DesiredGameState {
+    fn mirror(&mut self) {
+        self.ball_states.mirror();
+        self.car_states.mirror();
+    }
+}
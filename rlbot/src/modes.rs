@@ -0,0 +1,377 @@
+//! This module contains helpers for the game modes that don't play like
+//! standard soccar: rims and scoring zones for Hoops, the tile layout of
+//! Dropshot, the puck of Snow Day, and target goals for Heatseeker.
+//!
+//! [ModeHelpers::new] selects the right helpers from the
+//! [MatchConfiguration].
+//!
+//! Example:
+//! ```ignore
+//! use rlbot::modes::ModeHelpers;
+//!
+//! let mut mode = ModeHelpers::new(&match_configuration, &field_info);
+//!
+//! // In tick:
+//! if let ModeHelpers::Dropshot(dropshot) = &mut mode {
+//!     dropshot.update(&game_packet);
+//!     let open = dropshot.tiles().iter().filter(|tile| tile.state == TileState::Open);
+//! }
+//! ```
+
+use rlbot_flat::{
+    flat::{
+        BallInfo, CollisionShape, FieldInfo, GameMode, GamePacket, MatchConfiguration, MatchPhase,
+    },
+    glam::Vec3,
+};
+
+use crate::field::{Field, Goal};
+
+/// Height of the hoops rims above the floor.
+pub const HOOPS_RIM_HEIGHT: f32 = 365.;
+
+/// Distance from the center of a dropshot tile to its corners.
+pub const DROPSHOT_TILE_RADIUS: f32 = 443.405;
+/// Number of tiles in each row of one side of the dropshot arena, starting
+/// at the center line.
+const DROPSHOT_ROWS: [usize; 7] = [13, 12, 11, 10, 9, 8, 7];
+
+/// The arena a match is played in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arena {
+    Standard,
+    Hoops,
+    Dropshot,
+}
+
+impl Arena {
+    /// Detect the arena from the map, falling back to the game mode for
+    /// unknown maps.
+    #[must_use]
+    pub fn detect(match_configuration: &MatchConfiguration) -> Self {
+        let map = match_configuration.game_map_upk.to_lowercase();
+        if map.contains("hoops") {
+            Self::Hoops
+        } else if map.contains("shattershot") {
+            Self::Dropshot
+        } else {
+            match match_configuration.game_mode {
+                GameMode::Hoops => Self::Hoops,
+                GameMode::Dropshot => Self::Dropshot,
+                _ => Self::Standard,
+            }
+        }
+    }
+}
+
+/// Mode-specific helpers, selected by [ModeHelpers::new].
+#[derive(Debug, Clone, PartialEq)]
+pub enum ModeHelpers {
+    /// No special helpers are needed.
+    Standard,
+    Hoops(Hoops),
+    Dropshot(Dropshot),
+    Snowday,
+    Heatseeker(Heatseeker),
+}
+
+impl ModeHelpers {
+    #[must_use]
+    pub fn new(match_configuration: &MatchConfiguration, field_info: &FieldInfo) -> Self {
+        let field = Field::new(field_info);
+
+        match (
+            Arena::detect(match_configuration),
+            match_configuration.game_mode,
+        ) {
+            (Arena::Hoops, _) => Self::Hoops(Hoops::new(&field)),
+            (Arena::Dropshot, _) => Self::Dropshot(Dropshot::new()),
+            (Arena::Standard, GameMode::Snowday) => Self::Snowday,
+            (Arena::Standard, GameMode::Heatseeker) => Self::Heatseeker(Heatseeker::new(field)),
+            (Arena::Standard, _) => Self::Standard,
+        }
+    }
+}
+
+/// The shape of a ball, see [BallInfo::shape].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BallShape {
+    Sphere {
+        radius: f32,
+    },
+    /// A cylinder, like the Snow Day puck.
+    Puck {
+        radius: f32,
+        height: f32,
+    },
+    Box {
+        length: f32,
+        width: f32,
+        height: f32,
+    },
+}
+
+impl BallShape {
+    #[must_use]
+    pub fn of(ball: &BallInfo) -> Self {
+        match &ball.shape {
+            CollisionShape::SphereShape(sphere) => Self::Sphere {
+                radius: sphere.diameter / 2.,
+            },
+            CollisionShape::CylinderShape(cylinder) => Self::Puck {
+                radius: cylinder.diameter / 2.,
+                height: cylinder.height,
+            },
+            CollisionShape::BoxShape(shape) => Self::Box {
+                length: shape.length,
+                width: shape.width,
+                height: shape.height,
+            },
+        }
+    }
+
+    #[must_use]
+    pub fn is_puck(&self) -> bool {
+        matches!(self, Self::Puck { .. })
+    }
+
+    /// The distance from the center of the ball to the floor when it's
+    /// resting on it.
+    #[must_use]
+    pub fn rest_height(&self) -> f32 {
+        match *self {
+            Self::Sphere { radius } => radius,
+            Self::Puck { height, .. } | Self::Box { height, .. } => height / 2.,
+        }
+    }
+
+    /// The largest horizontal distance from the center of the ball to its
+    /// surface.
+    #[must_use]
+    pub fn horizontal_radius(&self) -> f32 {
+        match *self {
+            Self::Sphere { radius } | Self::Puck { radius, .. } => radius,
+            Self::Box { length, width, .. } => length.hypot(width) / 2.,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rim {
+    /// The team defending this rim.
+    pub team: u32,
+    /// The center of the rim.
+    pub center: Vec3,
+    pub radius: f32,
+}
+
+impl Rim {
+    /// Whether the whole ball is inside the net below the rim.
+    #[must_use]
+    pub fn is_in_scoring_zone(&self, ball: &BallInfo) -> bool {
+        let location = Vec3::from(ball.physics.location);
+        let radius = BallShape::of(ball).horizontal_radius();
+        location.z < self.center.z
+            && location.truncate().distance(self.center.truncate()) < self.radius - radius
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hoops {
+    pub rims: Vec<Rim>,
+}
+
+impl Hoops {
+    /// Build the rims from the goals of the field.
+    #[must_use]
+    pub fn new(field: &Field) -> Self {
+        Self {
+            rims: field
+                .goals
+                .iter()
+                .map(|goal| Rim {
+                    team: goal.team,
+                    center: goal.location.with_z(HOOPS_RIM_HEIGHT),
+                    radius: goal.width / 2.,
+                })
+                .collect(),
+        }
+    }
+
+    #[must_use]
+    pub fn own_rim(&self, team: u32) -> Option<&Rim> {
+        self.rims.iter().find(|rim| rim.team == team)
+    }
+
+    #[must_use]
+    pub fn opponent_rim(&self, team: u32) -> Option<&Rim> {
+        self.rims.iter().find(|rim| rim.team != team)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TileState {
+    Undamaged,
+    Damaged,
+    /// The tile is broken; a ball falling through it scores.
+    Open,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tile {
+    /// The team whose side the tile is on.
+    pub team: u32,
+    pub location: Vec3,
+    pub state: TileState,
+}
+
+/// The tiles of the dropshot arena, with their state estimated from the
+/// movement of the ball.
+///
+/// Packets don't contain the state of the tiles, so every bounce of the ball
+/// damages the tile it lands on, if the last touch was by the other team.
+/// Charged balls, which break several tiles at once, aren't accounted for.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dropshot {
+    tiles: Vec<Tile>,
+    last_velocity_z: Option<f32>,
+}
+
+impl Default for Dropshot {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Dropshot {
+    #[must_use]
+    pub fn new() -> Self {
+        let column_spacing = 3f32.sqrt() * DROPSHOT_TILE_RADIUS;
+        let row_spacing = 1.5 * DROPSHOT_TILE_RADIUS;
+
+        let tiles = [0, 1]
+            .into_iter()
+            .flat_map(|team| {
+                let side = if team == 0 { -1. } else { 1. };
+                DROPSHOT_ROWS
+                    .into_iter()
+                    .enumerate()
+                    .flat_map(move |(row, count)| {
+                        let y = side * (DROPSHOT_TILE_RADIUS + row as f32 * row_spacing);
+                        (0..count).map(move |i| Tile {
+                            team,
+                            location: Vec3::new(
+                                (i as f32 - (count - 1) as f32 / 2.) * column_spacing,
+                                y,
+                                0.,
+                            ),
+                            state: TileState::Undamaged,
+                        })
+                    })
+            })
+            .collect();
+
+        Self {
+            tiles,
+            last_velocity_z: None,
+        }
+    }
+
+    #[must_use]
+    pub fn tiles(&self) -> &[Tile] {
+        &self.tiles
+    }
+
+    /// The tile below `location`, if any.
+    #[must_use]
+    pub fn tile_at(&self, location: Vec3) -> Option<&Tile> {
+        self.tile_index_at(location).map(|i| &self.tiles[i])
+    }
+
+    fn tile_index_at(&self, location: Vec3) -> Option<usize> {
+        self.tiles
+            .iter()
+            .enumerate()
+            .map(|(i, tile)| (i, tile.location.truncate().distance(location.truncate())))
+            .filter(|&(_, distance)| distance < DROPSHOT_TILE_RADIUS)
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(i, _)| i)
+    }
+
+    pub fn update(&mut self, game_packet: &GamePacket) {
+        if matches!(
+            game_packet.match_info.match_phase,
+            MatchPhase::Countdown | MatchPhase::Kickoff
+        ) {
+            for tile in &mut self.tiles {
+                tile.state = TileState::Undamaged;
+            }
+        }
+
+        let Some(ball) = game_packet.balls.first() else {
+            return;
+        };
+
+        let location = Vec3::from(ball.physics.location);
+        let velocity_z = ball.physics.velocity.z;
+        let bounced = self.last_velocity_z.is_some_and(|last| last < 0.)
+            && velocity_z >= 0.
+            && location.z < BallShape::of(ball).rest_height() + 50.;
+        self.last_velocity_z = Some(velocity_z);
+
+        if !bounced {
+            return;
+        }
+
+        let last_touch_team = game_packet
+            .players
+            .iter()
+            .filter_map(|player| Some((player.team, player.latest_touch.as_ref()?.game_seconds)))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(team, _)| team);
+
+        if let Some(i) = self.tile_index_at(location) {
+            let tile = &mut self.tiles[i];
+            if last_touch_team.is_some_and(|team| team != tile.team) {
+                tile.state = match tile.state {
+                    TileState::Undamaged => TileState::Damaged,
+                    TileState::Damaged | TileState::Open => TileState::Open,
+                };
+            }
+        }
+    }
+}
+
+/// In Heatseeker, the ball homes in on the goal of the team that didn't touch
+/// it last.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Heatseeker {
+    pub field: Field,
+}
+
+impl Heatseeker {
+    #[must_use]
+    pub fn new(field: Field) -> Self {
+        Self { field }
+    }
+
+    /// The goal the ball is heading for, given the team that touched it last.
+    #[must_use]
+    pub fn target_goal(&self, last_touch_team: u32) -> Option<&Goal> {
+        self.field.opponent_goal(last_touch_team)
+    }
+
+    /// The goal the ball is heading for, estimated from its velocity. Useful
+    /// after kickoff, before anyone has touched the ball.
+    #[must_use]
+    pub fn target_goal_from_velocity(&self, ball: &BallInfo) -> Option<&Goal> {
+        let location = Vec3::from(ball.physics.location);
+        let velocity = Vec3::from(ball.physics.velocity);
+
+        self.field.goals.iter().max_by(|a, b| {
+            let alignment =
+                |goal: &Goal| (goal.location - location).normalize_or_zero().dot(velocity);
+            alignment(a).total_cmp(&alignment(b))
+        })
+    }
+}
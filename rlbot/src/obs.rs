@@ -0,0 +1,390 @@
+//! This module contains helpers for machine-learning bots: [ObservationBuilder],
+//! which turns a [GamePacket] into a flat vector of `f32`s, and the action
+//! parsers [DiscreteActions] and [parse_continuous], which turn the output of
+//! a model back into a [ControllerState].
+//!
+//! Observations are always from the perspective of blue. For orange, the
+//! packet is mirrored with [Mirror] so the bot attacks towards +y.
+//! Controller inputs are relative to the car and don't need mirroring back.
+//!
+//! Example:
+//! ```ignore
+//! use rlbot::obs::{DiscreteActions, ObservationBuilder};
+//!
+//! let builder = ObservationBuilder::new().with_boost_pads(34);
+//! let actions = DiscreteActions::lookup();
+//!
+//! // In tick:
+//! let observation = builder.build(&game_packet, index, Some(&ball_prediction)).unwrap();
+//! let action = model.predict(&observation);
+//! packet_queue.push(actions.player_input(player_index, action).unwrap());
+//! ```
+//!
+//! Parsing actions:
+//! ```rust
+//! use rlbot::obs::{DiscreteActions, parse_continuous};
+//!
+//! let actions = DiscreteActions::lookup();
+//! assert_eq!(actions.len(), 90);
+//! assert!(actions.parse(90).is_none());
+//!
+//! let controls = parse_continuous(&[1., -0.5, 0., 0., 0., 1., -1., 0.]).unwrap();
+//! assert_eq!(controls.steer, -0.5);
+//! assert!(controls.jump && !controls.boost);
+//! ```
+
+use rlbot_flat::{
+    flat::{AirState, BallPrediction, ControllerState, GamePacket, PlayerInfo, PlayerInput},
+    glam::{Mat3, Vec3},
+};
+
+pub use crate::mirror::is_mirrored;
+use crate::{ball_prediction::BallPredictionExt, controller::ControllerStateExt, mirror::Mirror};
+
+/// Locations and velocities are divided by this.
+const POSITION_SCALE: f32 = 2300.;
+/// Angular velocities are divided by this.
+const ANGULAR_SCALE: f32 = 5.5;
+
+/// The number of values per ball in an observation.
+pub const BALL_OBS_SIZE: usize = 9;
+/// The number of values per player in an observation.
+pub const PLAYER_OBS_SIZE: usize = 19;
+
+/// Convert a world vector into the frame of the team, where the team attacks
+/// towards +y. See [Mirror::for_team].
+#[must_use]
+pub fn to_team_frame(vector: Vec3, team: u32) -> Vec3 {
+    vector.for_team(team)
+}
+
+/// The order of the other players in an observation, within teammates and
+/// within opponents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PlayerOrder {
+    /// By index in [GamePacket::players].
+    #[default]
+    Index,
+    /// Nearest to the observing player first.
+    Distance,
+}
+
+/// Builds fixed-size observations, normalized to roughly `[-1, 1]`.
+///
+/// An observation contains, in order:
+/// - the first ball: location, velocity, and angular velocity;
+/// - the observing player, then its teammates, then its opponents: location,
+///   velocity, forward, up, angular velocity, boost, whether it's on the
+///   ground, whether it can still flip, and whether it's demolished;
+/// - if enabled, whether every boost pad is active;
+/// - if enabled, the predicted location of the ball at every sample time.
+///
+/// Missing players, pads, and samples are filled with zeros, and extra ones
+/// are left out, so the size only depends on the configuration.
+#[derive(Debug, Clone)]
+pub struct ObservationBuilder {
+    player_order: PlayerOrder,
+    max_teammates: usize,
+    max_opponents: usize,
+    boost_pads: usize,
+    prediction_samples: Vec<f32>,
+}
+
+impl Default for ObservationBuilder {
+    /// Room for a 3v3 match, without boost pads and prediction samples.
+    fn default() -> Self {
+        Self {
+            player_order: PlayerOrder::Index,
+            max_teammates: 2,
+            max_opponents: 3,
+            boost_pads: 0,
+            prediction_samples: Vec::new(),
+        }
+    }
+}
+
+impl ObservationBuilder {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub fn with_player_order(mut self, player_order: PlayerOrder) -> Self {
+        self.player_order = player_order;
+        self
+    }
+
+    #[must_use]
+    pub fn with_max_players(mut self, max_teammates: usize, max_opponents: usize) -> Self {
+        self.max_teammates = max_teammates;
+        self.max_opponents = max_opponents;
+        self
+    }
+
+    /// Include the state of `count` boost pads. There are 34 on the standard
+    /// soccar field. For orange, pads are in reverse order, which maps every
+    /// pad to its mirrored counterpart on symmetric fields.
+    #[must_use]
+    pub fn with_boost_pads(mut self, count: usize) -> Self {
+        self.boost_pads = count;
+        self
+    }
+
+    /// Include the predicted location of the ball at every one of the given
+    /// seconds into the future.
+    #[must_use]
+    pub fn with_prediction_samples(mut self, seconds: impl Into<Vec<f32>>) -> Self {
+        self.prediction_samples = seconds.into();
+        self
+    }
+
+    /// The number of values in every observation.
+    #[must_use]
+    pub fn size(&self) -> usize {
+        BALL_OBS_SIZE
+            + PLAYER_OBS_SIZE * (1 + self.max_teammates + self.max_opponents)
+            + self.boost_pads
+            + 3 * self.prediction_samples.len()
+    }
+
+    /// Build the observation of the player at `player_index`. Returns `None`
+    /// if there's no such player.
+    #[must_use]
+    pub fn build(
+        &self,
+        game_packet: &GamePacket,
+        player_index: usize,
+        ball_prediction: Option<&BallPrediction>,
+    ) -> Option<Vec<f32>> {
+        let player = game_packet.players.get(player_index)?;
+        let team = player.team;
+        let mut obs = Vec::with_capacity(self.size());
+
+        match game_packet.balls.first() {
+            Some(ball) => {
+                push_vector(&mut obs, ball.physics.location.into(), team, POSITION_SCALE);
+                push_vector(&mut obs, ball.physics.velocity.into(), team, POSITION_SCALE);
+                push_vector(
+                    &mut obs,
+                    ball.physics.angular_velocity.into(),
+                    team,
+                    ANGULAR_SCALE,
+                );
+            }
+            None => obs.extend([0.; BALL_OBS_SIZE]),
+        }
+
+        push_player(&mut obs, player, team);
+        let (teammates, opponents): (Vec<_>, Vec<_>) = game_packet
+            .players
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| i != player_index)
+            .map(|(_, other)| other)
+            .partition(|other| other.team == team);
+        for (mut others, max) in [
+            (teammates, self.max_teammates),
+            (opponents, self.max_opponents),
+        ] {
+            if self.player_order == PlayerOrder::Distance {
+                let location = Vec3::from(player.physics.location);
+                others.sort_by(|a, b| {
+                    let distance = |other: &PlayerInfo| {
+                        Vec3::from(other.physics.location).distance_squared(location)
+                    };
+                    distance(a).total_cmp(&distance(b))
+                });
+            }
+
+            for other in others.iter().take(max) {
+                push_player(&mut obs, other, team);
+            }
+            obs.resize(
+                obs.len() + PLAYER_OBS_SIZE * max.saturating_sub(others.len()),
+                0.,
+            );
+        }
+
+        let mut pads: Vec<_> = game_packet
+            .boost_pads
+            .iter()
+            .map(|pad| if pad.is_active { 1. } else { 0. })
+            .collect();
+        if is_mirrored(team) {
+            pads.reverse();
+        }
+        pads.resize(self.boost_pads, 0.);
+        obs.extend(pads);
+
+        let now = game_packet.match_info.seconds_elapsed;
+        for &seconds in &self.prediction_samples {
+            match ball_prediction.and_then(|prediction| prediction.interpolate(now + seconds)) {
+                Some(slice) => {
+                    push_vector(
+                        &mut obs,
+                        slice.physics.location.into(),
+                        team,
+                        POSITION_SCALE,
+                    );
+                }
+                None => obs.extend([0.; 3]),
+            }
+        }
+
+        Some(obs)
+    }
+}
+
+fn push_vector(obs: &mut Vec<f32>, vector: Vec3, team: u32, scale: f32) {
+    obs.extend((to_team_frame(vector, team) / scale).to_array());
+}
+
+fn push_player(obs: &mut Vec<f32>, player: &PlayerInfo, team: u32) {
+    let physics = &player.physics;
+    let orientation = Mat3::from(physics.rotation);
+    let on_ground = player.air_state == AirState::OnGround;
+    let has_flip = on_ground
+        || (!player.has_double_jumped && !player.has_dodged && player.dodge_timeout != 0.);
+
+    push_vector(obs, physics.location.into(), team, POSITION_SCALE);
+    push_vector(obs, physics.velocity.into(), team, POSITION_SCALE);
+    push_vector(obs, orientation.x_axis, team, 1.);
+    push_vector(obs, orientation.z_axis, team, 1.);
+    push_vector(obs, physics.angular_velocity.into(), team, ANGULAR_SCALE);
+    obs.extend([
+        player.boost / 100.,
+        f32::from(u8::from(on_ground)),
+        f32::from(u8::from(has_flip)),
+        f32::from(u8::from(player.demolished_timeout > 0.)),
+    ]);
+}
+
+/// Parse a continuous action of 8 values: throttle, steer, pitch, yaw, and
+/// roll, which are clamped to `[-1, 1]`, then jump, boost, and handbrake,
+/// which are pressed when positive. Returns `None` if there aren't exactly 8
+/// values.
+#[must_use]
+pub fn parse_continuous(action: &[f32]) -> Option<ControllerState> {
+    let &[throttle, steer, pitch, yaw, roll, jump, boost, handbrake] = action else {
+        return None;
+    };
+
+    let controller_state = ControllerState {
+        throttle,
+        steer,
+        pitch,
+        yaw,
+        roll,
+        jump: jump > 0.,
+        boost: boost > 0.,
+        handbrake: handbrake > 0.,
+        ..Default::default()
+    };
+    Some(controller_state.clamped())
+}
+
+/// Maps action indices to [ControllerState]s with a lookup table.
+#[derive(Debug, Clone)]
+pub struct DiscreteActions {
+    table: Vec<ControllerState>,
+}
+
+impl DiscreteActions {
+    #[must_use]
+    pub fn from_table(table: Vec<ControllerState>) -> Self {
+        Self { table }
+    }
+
+    /// The commonly used table of 90 actions: every combination of throttle,
+    /// steer, boost, and handbrake on the ground, where boosting implies full
+    /// throttle, and every useful combination of pitch, yaw, roll, jump, and
+    /// boost in the air.
+    #[must_use]
+    pub fn lookup() -> Self {
+        const AXIS: [f32; 3] = [-1., 0., 1.];
+        let mut table = Vec::new();
+
+        for throttle in AXIS {
+            for steer in AXIS {
+                for boost in [false, true] {
+                    for handbrake in [false, true] {
+                        if boost && throttle != 1. {
+                            continue;
+                        }
+                        table.push(ControllerState {
+                            throttle: if boost { 1. } else { throttle },
+                            steer,
+                            yaw: steer,
+                            boost,
+                            handbrake,
+                            ..Default::default()
+                        });
+                    }
+                }
+            }
+        }
+
+        for pitch in AXIS {
+            for yaw in AXIS {
+                for roll in AXIS {
+                    for jump in [false, true] {
+                        for boost in [false, true] {
+                            // Yaw doesn't matter for flips, roll picks the direction
+                            if jump && yaw != 0. {
+                                continue;
+                            }
+                            // Already covered by the ground actions
+                            if pitch == 0. && roll == 0. && !jump {
+                                continue;
+                            }
+                            table.push(ControllerState {
+                                throttle: if boost { 1. } else { 0. },
+                                steer: yaw,
+                                pitch,
+                                yaw,
+                                roll,
+                                jump,
+                                boost,
+                                handbrake: jump && (pitch != 0. || yaw != 0. || roll != 0.),
+                                ..Default::default()
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        Self { table }
+    }
+
+    #[must_use]
+    pub fn table(&self) -> &[ControllerState] {
+        &self.table
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.table.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.table.is_empty()
+    }
+
+    #[must_use]
+    pub fn parse(&self, index: usize) -> Option<ControllerState> {
+        self.table.get(index).copied()
+    }
+
+    /// The [PlayerInput] for the action, ready to be pushed to a
+    /// [PacketQueue](crate::util::PacketQueue).
+    #[must_use]
+    pub fn player_input(&self, player_index: u32, index: usize) -> Option<PlayerInput> {
+        Some(PlayerInput {
+            player_index,
+            controller_state: self.parse(index)?,
+        })
+    }
+}
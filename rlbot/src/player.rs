@@ -0,0 +1,224 @@
+//! This module contains [PlayerInfoExt], with kinematics and orientation
+//! helpers for cars, and [DemolitionTracker], which detects demolitions and
+//! respawns across packets.
+//!
+//! Example:
+//! ```ignore
+//! use rlbot::player::PlayerInfoExt;
+//!
+//! let car = &game_packet.players[index];
+//! let ball_location = game_packet.balls[0].physics.location.into();
+//!
+//! let controller = ControllerState {
+//!     throttle: 1.,
+//!     steer: car.angle_to(ball_location).clamp(-1., 1.),
+//!     ..Default::default()
+//! };
+//! ```
+
+use std::f32::consts::PI;
+
+use rlbot_flat::{
+    flat::{AirState, GamePacket, PlayerInfo},
+    glam::{Mat3, Vec3},
+};
+
+pub use crate::constants::MAX_CAR_SPEED;
+use crate::constants::{DEFAULT_BOOST_ACCELERATION, PhysicsConstants};
+
+/// The maximum speed a car can reach by throttling alone.
+pub const MAX_DRIVE_SPEED: f32 = 1410.;
+
+/// Rough average accelerations used by [PlayerInfoExt::time_to_reach].
+const THROTTLE_ACCELERATION: f32 = 1000.;
+const BOOST_ACCELERATION: f32 = 1600.;
+/// Rough average yaw rate used by [PlayerInfoExt::time_to_reach].
+const TURN_RATE: f32 = PI;
+
+/// The surface a car is on, see [PlayerInfoExt::surface].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Surface {
+    Ground,
+    Wall,
+    Ceiling,
+    Air,
+}
+
+pub trait PlayerInfoExt {
+    fn location(&self) -> Vec3;
+
+    fn velocity(&self) -> Vec3;
+
+    fn orientation(&self) -> Mat3;
+
+    fn forward(&self) -> Vec3;
+
+    fn right(&self) -> Vec3;
+
+    fn up(&self) -> Vec3;
+
+    fn to_local(&self, point: Vec3) -> Vec3;
+
+    fn forward_speed(&self) -> f32;
+
+    fn angle_to(&self, target: Vec3) -> f32;
+
+    fn time_to_reach(&self, target: Vec3) -> f32;
+
+    fn time_to_reach_with(&self, target: Vec3, constants: &PhysicsConstants) -> f32;
+
+    fn surface(&self) -> Surface;
+
+    fn is_demolished(&self) -> bool;
+}
+
+impl PlayerInfoExt for PlayerInfo {
+    fn location(&self) -> Vec3 {
+        self.physics.location.into()
+    }
+
+    fn velocity(&self) -> Vec3 {
+        self.physics.velocity.into()
+    }
+
+    /// The orientation matrix, with forward, right, and up as columns.
+    fn orientation(&self) -> Mat3 {
+        self.physics.rotation.into()
+    }
+
+    fn forward(&self) -> Vec3 {
+        self.orientation().x_axis
+    }
+
+    fn right(&self) -> Vec3 {
+        self.orientation().y_axis
+    }
+
+    fn up(&self) -> Vec3 {
+        self.orientation().z_axis
+    }
+
+    /// Transform a world point into car-local coordinates, where x is
+    /// forward, y is right, and z is up.
+    fn to_local(&self, point: Vec3) -> Vec3 {
+        self.orientation().transpose() * (point - self.location())
+    }
+
+    /// The speed in the direction the car is facing. Negative when reversing.
+    fn forward_speed(&self) -> f32 {
+        self.velocity().dot(self.forward())
+    }
+
+    /// The angle between the nose of the car and the target, in radians
+    /// within `[-π, π]`. Positive when the target is to the right.
+    fn angle_to(&self, target: Vec3) -> f32 {
+        let local = self.to_local(target);
+        local.y.atan2(local.x)
+    }
+
+    /// A rough estimate of the seconds needed to drive to the target,
+    /// accounting for turning and for accelerating with the available boost.
+    fn time_to_reach(&self, target: Vec3) -> f32 {
+        self.time_to_reach_with(target, &PhysicsConstants::default())
+    }
+
+    /// [time_to_reach](Self::time_to_reach) taking boost mutators into
+    /// account.
+    fn time_to_reach_with(&self, target: Vec3, constants: &PhysicsConstants) -> f32 {
+        let distance = self.location().distance(target);
+        let turn_time = self.angle_to(target).abs() / TURN_RATE;
+
+        let can_boost = constants.boost_available && (self.boost > 0. || constants.unlimited_boost);
+        let (acceleration, max_speed) = if can_boost {
+            let boost_strength = constants.boost_acceleration / DEFAULT_BOOST_ACCELERATION;
+            (
+                THROTTLE_ACCELERATION
+                    + (BOOST_ACCELERATION - THROTTLE_ACCELERATION) * boost_strength,
+                constants.car_max_speed,
+            )
+        } else {
+            (THROTTLE_ACCELERATION, MAX_DRIVE_SPEED)
+        };
+
+        let speed = self.forward_speed().clamp(0., max_speed);
+        let accelerate_time = (max_speed - speed) / acceleration;
+        let accelerate_distance = (speed + max_speed) / 2. * accelerate_time;
+
+        let drive_time = if distance <= accelerate_distance {
+            // Solve distance = speed * t + acceleration / 2 * t^2
+            ((speed * speed + 2. * acceleration * distance).sqrt() - speed) / acceleration
+        } else {
+            accelerate_time + (distance - accelerate_distance) / max_speed
+        };
+
+        turn_time + drive_time
+    }
+
+    /// Classify the surface the car is on by the direction of its roof.
+    fn surface(&self) -> Surface {
+        if self.air_state != AirState::OnGround {
+            return Surface::Air;
+        }
+
+        match self.up().z {
+            z if z > 0.7 => Surface::Ground,
+            z if z < -0.7 => Surface::Ceiling,
+            _ => Surface::Wall,
+        }
+    }
+
+    fn is_demolished(&self) -> bool {
+        self.demolished_timeout > 0.
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DemolitionEvent {
+    Demolished { player_index: usize },
+    Respawned { player_index: usize },
+}
+
+/// Detects demolitions and respawns by comparing consecutive packets.
+#[derive(Debug, Clone, Default)]
+pub struct DemolitionTracker {
+    demolished: Vec<bool>,
+}
+
+impl DemolitionTracker {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Update the tracker with a new packet. Returns the demolitions and
+    /// respawns that happened since the previous packet.
+    pub fn update(&mut self, game_packet: &GamePacket) -> Vec<DemolitionEvent> {
+        let demolished: Vec<_> = game_packet
+            .players
+            .iter()
+            .map(PlayerInfo::is_demolished)
+            .collect();
+
+        let events = demolished
+            .iter()
+            .enumerate()
+            .filter_map(|(player_index, &is_demolished)| {
+                let was_demolished = self.demolished.get(player_index).copied()?;
+                match (was_demolished, is_demolished) {
+                    (false, true) => Some(DemolitionEvent::Demolished { player_index }),
+                    (true, false) => Some(DemolitionEvent::Respawned { player_index }),
+                    _ => None,
+                }
+            })
+            .collect();
+
+        self.demolished = demolished;
+        events
+    }
+
+    /// Whether the player was demolished in the last packet.
+    #[must_use]
+    pub fn is_demolished(&self, player_index: usize) -> bool {
+        self.demolished.get(player_index).copied().unwrap_or(false)
+    }
+}
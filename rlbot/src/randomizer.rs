@@ -0,0 +1,228 @@
+//! This module contains [StateRandomizer], which generates random, valid
+//! [DesiredGameState]s for training data collection.
+//!
+//! Randomness comes from a [RandomSource]. [SplitMix64] is provided as a small
+//! seedable generator, so runs are reproducible; implement [RandomSource] for
+//! your own generator to use something else.
+//!
+//! Example:
+//! ```rust
+//! use rlbot::randomizer::{SplitMix64, StateRandomizer};
+//!
+//! let mut rng = SplitMix64::new(42);
+//! let randomizer = StateRandomizer::default();
+//!
+//! let state = randomizer.generate(&mut rng, 2, 1);
+//! assert_eq!(state.car_states.len(), 2);
+//! assert_eq!(state.ball_states.len(), 1);
+//! ```
+
+use std::{
+    f32::consts::{FRAC_PI_2, PI, SQRT_2},
+    ops::RangeInclusive,
+};
+
+use rlbot_flat::{
+    flat::{DesiredBallState, DesiredCarState, DesiredGameState, Rotator},
+    glam::Vec3,
+};
+
+use crate::{
+    constants::{BALL_RADIUS, CAR_REST_HEIGHT},
+    field::{BACK_WALL_Y, CEILING_Z, CORNER_WALL_XY, SIDE_WALL_X},
+    state_builder::{DesiredCarStateExt, DesiredPhysicsExt},
+};
+
+/// Random floor points that hit a corner wall are retried this many times.
+const MAX_FLOOR_POINT_ATTEMPTS: usize = 100;
+
+/// A source of random numbers.
+pub trait RandomSource {
+    fn next_u64(&mut self) -> u64;
+
+    /// A random number in `[0, 1)`.
+    fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// A random number in the given range.
+    fn range(&mut self, range: RangeInclusive<f32>) -> f32 {
+        range.start() + (range.end() - range.start()) * self.next_f32()
+    }
+
+    /// A random unit vector.
+    fn direction(&mut self) -> Vec3 {
+        let z = self.range(-1.0..=1.);
+        let angle = self.range(-PI..=PI);
+        let r = (1. - z * z).sqrt();
+        Vec3::new(r * angle.cos(), r * angle.sin(), z)
+    }
+}
+
+/// A small, fast, seedable random number generator.
+#[derive(Debug, Clone)]
+pub struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    #[must_use]
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+}
+
+impl RandomSource for SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+/// How cars are randomized.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CarDistribution {
+    /// The probability of a car spawning in the air instead of on the ground.
+    pub airborne_chance: f32,
+    /// The height of cars spawning in the air.
+    pub air_height: RangeInclusive<f32>,
+    pub max_speed: f32,
+    pub max_angular_speed: f32,
+    pub boost: RangeInclusive<f32>,
+}
+
+impl Default for CarDistribution {
+    fn default() -> Self {
+        Self {
+            airborne_chance: 0.2,
+            air_height: 200.0..=1500.,
+            max_speed: 2300.,
+            max_angular_speed: 5.5,
+            boost: 0.0..=100.,
+        }
+    }
+}
+
+/// How balls are randomized.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BallDistribution {
+    pub height: RangeInclusive<f32>,
+    pub max_speed: f32,
+    pub max_angular_speed: f32,
+}
+
+impl Default for BallDistribution {
+    fn default() -> Self {
+        Self {
+            height: BALL_RADIUS..=1500.,
+            max_speed: 3000.,
+            max_angular_speed: 6.,
+        }
+    }
+}
+
+/// Generates random states inside the standard soccar arena.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateRandomizer {
+    pub cars: CarDistribution,
+    pub balls: BallDistribution,
+    /// The minimum distance between spawned objects and the walls. Clamped
+    /// between 0 and half the width of the field, where only the center line
+    /// is left.
+    pub wall_margin: f32,
+}
+
+impl Default for StateRandomizer {
+    fn default() -> Self {
+        Self {
+            cars: CarDistribution::default(),
+            balls: BallDistribution::default(),
+            wall_margin: 200.,
+        }
+    }
+}
+
+impl StateRandomizer {
+    /// Generate a state for the given number of cars and balls.
+    pub fn generate(
+        &self,
+        rng: &mut impl RandomSource,
+        num_cars: usize,
+        num_balls: usize,
+    ) -> DesiredGameState {
+        DesiredGameState {
+            car_states: (0..num_cars).map(|_| self.random_car(rng)).collect(),
+            ball_states: (0..num_balls).map(|_| self.random_ball(rng)).collect(),
+            ..Default::default()
+        }
+    }
+
+    pub fn random_car(&self, rng: &mut impl RandomSource) -> DesiredCarState {
+        let mut car = DesiredCarState::default();
+        let (x, y) = self.random_floor_point(rng);
+
+        if rng.next_f32() < self.cars.airborne_chance {
+            let z = rng.range(self.clamp_height(self.cars.air_height.clone(), CAR_REST_HEIGHT));
+            car.set_location(Vec3::new(x, y, z));
+            car.set_rotation(Rotator {
+                pitch: rng.range(-FRAC_PI_2..=FRAC_PI_2),
+                yaw: rng.range(-PI..=PI),
+                roll: rng.range(-PI..=PI),
+            });
+            car.set_velocity(rng.direction() * rng.range(0.0..=self.cars.max_speed));
+        } else {
+            let yaw = rng.range(-PI..=PI);
+            car.set_location(Vec3::new(x, y, CAR_REST_HEIGHT));
+            car.set_rotation(Rotator {
+                pitch: 0.,
+                yaw,
+                roll: 0.,
+            });
+            let speed = rng.range(0.0..=self.cars.max_speed);
+            car.set_velocity(Vec3::new(yaw.cos(), yaw.sin(), 0.) * speed);
+        }
+
+        car.set_angular_velocity(rng.direction() * rng.range(0.0..=self.cars.max_angular_speed));
+        car.set_boost(rng.range(self.cars.boost.clone()));
+        car
+    }
+
+    pub fn random_ball(&self, rng: &mut impl RandomSource) -> DesiredBallState {
+        let mut ball = DesiredBallState::default();
+        let (x, y) = self.random_floor_point(rng);
+        let z = rng.range(self.clamp_height(self.balls.height.clone(), BALL_RADIUS));
+
+        ball.set_location(Vec3::new(x, y, z));
+        ball.set_velocity(rng.direction() * rng.range(0.0..=self.balls.max_speed));
+        ball.set_angular_velocity(rng.direction() * rng.range(0.0..=self.balls.max_angular_speed));
+        ball
+    }
+
+    /// A random point on the floor, at least [Self::wall_margin] away from
+    /// the side, back, and corner walls.
+    /// Falls back to the center of the field if no point is found.
+    fn random_floor_point(&self, rng: &mut impl RandomSource) -> (f32, f32) {
+        let wall_margin = self.clamped_wall_margin();
+        let max_x = SIDE_WALL_X - wall_margin;
+        let max_y = BACK_WALL_Y - wall_margin;
+
+        (0..MAX_FLOOR_POINT_ATTEMPTS)
+            .map(|_| (rng.range(-max_x..=max_x), rng.range(-max_y..=max_y)))
+            .find(|&(x, y)| (CORNER_WALL_XY - x.abs() - y.abs()) / SQRT_2 >= wall_margin)
+            .unwrap_or((0., 0.))
+    }
+
+    fn clamped_wall_margin(&self) -> f32 {
+        self.wall_margin.clamp(0., SIDE_WALL_X)
+    }
+
+    /// Keep heights between `rest_height` and [Self::wall_margin] below the
+    /// ceiling, or at `rest_height` if the margin leaves no room.
+    fn clamp_height(&self, range: RangeInclusive<f32>, rest_height: f32) -> RangeInclusive<f32> {
+        let max = (CEILING_Z - self.clamped_wall_margin()).max(rest_height);
+        range.start().clamp(rest_height, max)..=range.end().clamp(rest_height, max)
+    }
+}
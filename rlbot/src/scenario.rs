@@ -0,0 +1,307 @@
+//! This module contains a small framework for training drills, meant to be
+//! driven by a [ScriptAgent](crate::agents::ScriptAgent).
+//!
+//! A [Drill] sets up a state, decides when an attempt has succeeded or failed,
+//! and scores it. A [DrillRunner] cycles through drills, resetting the state
+//! after every attempt and collecting the results.
+//!
+//! Example:
+//! ```ignore
+//! use rlbot::scenario::{Drill, DrillContext, DrillRunner, DrillStatus};
+//! use rlbot::state_builder::{DesiredGameStateExt, DesiredPhysicsExt};
+//!
+//! struct OpenNetShot;
+//!
+//! impl Drill for OpenNetShot {
+//!     fn name(&self) -> &str {
+//!         "Open net shot"
+//!     }
+//!
+//!     fn setup(&mut self, _game_packet: &GamePacket, state: &mut DesiredGameState) {
+//!         state.mod_ball(0, |b| {
+//!             b.set_location(Vector3 { x: 0., y: 3000., z: 93. });
+//!             b.set_velocity(Vector3::default());
+//!         });
+//!         state.mod_car(0, |c| {
+//!             c.set_location(Vector3 { x: 0., y: 0., z: 17. });
+//!             c.set_velocity(Vector3::default());
+//!         });
+//!     }
+//!
+//!     fn evaluate(&mut self, context: &DrillContext) -> DrillStatus {
+//!         match context.goal_scored_by {
+//!             Some(0) => DrillStatus::Success,
+//!             Some(_) => DrillStatus::Failure,
+//!             None => DrillStatus::Running,
+//!         }
+//!     }
+//! }
+//!
+//! let mut runner = DrillRunner::new(vec![Box::new(OpenNetShot)]);
+//!
+//! // In tick:
+//! if let Some(result) = runner.tick(&game_packet, packet_queue) {
+//!     println!("{}: {:?} in {:.2}s", result.drill, result.outcome, result.elapsed);
+//! }
+//! ```
+
+use rlbot_flat::flat::{DesiredGameState, GamePacket, MatchPhase};
+
+use crate::util::PacketQueue;
+
+/// The status of an attempt, as decided by [Drill::evaluate].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DrillStatus {
+    Running,
+    Success,
+    Failure,
+}
+
+/// How an attempt ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DrillOutcome {
+    Success,
+    Failure,
+    /// The attempt took longer than [Drill::timeout].
+    Timeout,
+}
+
+/// Information about the current attempt, passed to [Drill::evaluate].
+pub struct DrillContext<'a> {
+    pub game_packet: &'a GamePacket,
+    /// Seconds since the attempt started.
+    pub elapsed: f32,
+    /// The team that scored since the attempt started, if any.
+    pub goal_scored_by: Option<u32>,
+    /// Indices of the cars that touched the ball since the attempt started,
+    /// in order of their first touch.
+    pub touches: &'a [usize],
+}
+
+pub trait Drill {
+    fn name(&self) -> &str;
+
+    /// Seconds after which an attempt ends with [DrillOutcome::Timeout].
+    fn timeout(&self) -> f32 {
+        10.
+    }
+
+    /// Set up the state for a new attempt.
+    fn setup(&mut self, game_packet: &GamePacket, state: &mut DesiredGameState);
+
+    /// Decide whether the attempt has ended.
+    fn evaluate(&mut self, context: &DrillContext) -> DrillStatus;
+
+    /// Score a finished attempt. By default, successful attempts score higher
+    /// the faster they are.
+    fn score(&self, outcome: DrillOutcome, elapsed: f32) -> f32 {
+        match outcome {
+            DrillOutcome::Success => 1. - (elapsed / self.timeout()).clamp(0., 1.) / 2.,
+            DrillOutcome::Failure | DrillOutcome::Timeout => 0.,
+        }
+    }
+}
+
+/// The result of a single attempt.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DrillResult {
+    pub drill: String,
+    pub outcome: DrillOutcome,
+    pub elapsed: f32,
+    pub score: f32,
+}
+
+/// Aggregated results of all attempts of a drill.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DrillSummary {
+    pub drill: String,
+    pub attempts: usize,
+    pub successes: usize,
+    pub average_score: f32,
+}
+
+enum Phase {
+    NeedsSetup,
+    Settling { until: f32 },
+    Running { start: f32 },
+}
+
+/// Cycles through a list of drills, resetting the state after every attempt.
+pub struct DrillRunner {
+    drills: Vec<Box<dyn Drill>>,
+    current: usize,
+    phase: Phase,
+    settle_time: f32,
+    start_scores: Vec<u32>,
+    start_touches: Vec<Option<f32>>,
+    touches: Vec<usize>,
+    results: Vec<DrillResult>,
+}
+
+impl DrillRunner {
+    #[must_use]
+    pub fn new(drills: Vec<Box<dyn Drill>>) -> Self {
+        Self {
+            drills,
+            current: 0,
+            phase: Phase::NeedsSetup,
+            settle_time: 0.1,
+            start_scores: Vec::new(),
+            start_touches: Vec::new(),
+            touches: Vec::new(),
+            results: Vec::new(),
+        }
+    }
+
+    /// Seconds to wait after setting the state before an attempt starts,
+    /// giving core time to apply it.
+    #[must_use]
+    pub fn with_settle_time(mut self, seconds: f32) -> Self {
+        self.settle_time = seconds;
+        self
+    }
+
+    /// The drill currently being attempted.
+    #[must_use]
+    pub fn current_drill(&self) -> Option<&dyn Drill> {
+        self.drills.get(self.current).map(|drill| &**drill)
+    }
+
+    /// Advance the runner. Returns the result of an attempt when it ends.
+    pub fn tick(
+        &mut self,
+        game_packet: &GamePacket,
+        packet_queue: &mut PacketQueue,
+    ) -> Option<DrillResult> {
+        let phase = game_packet.match_info.match_phase;
+        if self.drills.is_empty() || !matches!(phase, MatchPhase::Active | MatchPhase::GoalScored) {
+            return None;
+        }
+
+        let now = game_packet.match_info.seconds_elapsed;
+
+        match self.phase {
+            Phase::NeedsSetup => {
+                if phase != MatchPhase::Active {
+                    return None;
+                }
+
+                let mut state = DesiredGameState::default();
+                self.drills[self.current].setup(game_packet, &mut state);
+                packet_queue.push(state);
+
+                self.phase = Phase::Settling {
+                    until: now + self.settle_time,
+                };
+                None
+            }
+            Phase::Settling { until } => {
+                if now >= until {
+                    self.start_scores = team_scores(game_packet);
+                    self.start_touches = latest_touches(game_packet);
+                    self.touches.clear();
+                    self.phase = Phase::Running { start: now };
+                }
+                None
+            }
+            Phase::Running { start } => {
+                self.track_touches(game_packet);
+
+                let elapsed = now - start;
+                let context = DrillContext {
+                    game_packet,
+                    elapsed,
+                    goal_scored_by: self.goal_scored_by(game_packet),
+                    touches: &self.touches,
+                };
+
+                let drill = &mut self.drills[self.current];
+                let outcome = match drill.evaluate(&context) {
+                    DrillStatus::Success => DrillOutcome::Success,
+                    DrillStatus::Failure => DrillOutcome::Failure,
+                    DrillStatus::Running if elapsed >= drill.timeout() => DrillOutcome::Timeout,
+                    DrillStatus::Running => return None,
+                };
+
+                let result = DrillResult {
+                    drill: drill.name().to_string(),
+                    outcome,
+                    elapsed,
+                    score: drill.score(outcome, elapsed),
+                };
+
+                self.results.push(result.clone());
+                self.current = (self.current + 1) % self.drills.len();
+                self.phase = Phase::NeedsSetup;
+
+                Some(result)
+            }
+        }
+    }
+
+    /// The results of all finished attempts, in order.
+    #[must_use]
+    pub fn results(&self) -> &[DrillResult] {
+        &self.results
+    }
+
+    /// The results aggregated per drill, in the order the drills were given.
+    #[must_use]
+    pub fn summary(&self) -> Vec<DrillSummary> {
+        self.drills
+            .iter()
+            .map(|drill| {
+                let results = self.results.iter().filter(|r| r.drill == drill.name());
+                let (attempts, successes, total_score) =
+                    results.fold((0, 0, 0.), |(attempts, successes, total), r| {
+                        (
+                            attempts + 1,
+                            successes + usize::from(r.outcome == DrillOutcome::Success),
+                            total + r.score,
+                        )
+                    });
+
+                DrillSummary {
+                    drill: drill.name().to_string(),
+                    attempts,
+                    successes,
+                    average_score: if attempts == 0 {
+                        0.
+                    } else {
+                        total_score / attempts as f32
+                    },
+                }
+            })
+            .collect()
+    }
+
+    fn goal_scored_by(&self, game_packet: &GamePacket) -> Option<u32> {
+        game_packet
+            .teams
+            .iter()
+            .zip(&self.start_scores)
+            .find(|(team, start_score)| team.score > **start_score)
+            .map(|(team, _)| team.team_index)
+    }
+
+    fn track_touches(&mut self, game_packet: &GamePacket) {
+        for (i, touch) in latest_touches(game_packet).into_iter().enumerate() {
+            let start_touch = self.start_touches.get(i).copied().flatten();
+            if touch != start_touch && !self.touches.contains(&i) {
+                self.touches.push(i);
+            }
+        }
+    }
+}
+
+fn team_scores(game_packet: &GamePacket) -> Vec<u32> {
+    game_packet.teams.iter().map(|team| team.score).collect()
+}
+
+fn latest_touches(game_packet: &GamePacket) -> Vec<Option<f32>> {
+    game_packet
+        .players
+        .iter()
+        .map(|player| player.latest_touch.as_ref().map(|touch| touch.game_seconds))
+        .collect()
+}
@@ -0,0 +1,154 @@
+//! This module contains [GameSnapshot], which captures a moment of a match so
+//! it can be restored, fully or partially, through state setting.
+//!
+//! Example:
+//! ```ignore
+//! use rlbot::snapshot::{GameSnapshot, RestoreFilter};
+//!
+//! // Save an interesting moment
+//! let snapshot = GameSnapshot::capture(&game_packet);
+//!
+//! // Later, put only the ball and the blue cars back
+//! packet_queue.push(snapshot.restore(&RestoreFilter::team_and_balls(0)));
+//! ```
+
+use rlbot_flat::flat::{DesiredCarState, DesiredGameState, GamePacket};
+
+use crate::state_builder::DesiredGameStateExt;
+
+/// Which cars to restore.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum CarSelection {
+    #[default]
+    All,
+    None,
+    /// Only cars on the given team.
+    Team(u32),
+    /// Only cars at the given indices.
+    Indices(Vec<usize>),
+}
+
+/// Which parts of a [GameSnapshot] to restore.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RestoreFilter {
+    pub cars: CarSelection,
+    pub balls: bool,
+    /// Restore game speed and gravity.
+    pub match_info: bool,
+}
+
+impl Default for RestoreFilter {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+impl RestoreFilter {
+    /// Restore everything.
+    #[must_use]
+    pub fn all() -> Self {
+        Self {
+            cars: CarSelection::All,
+            balls: true,
+            match_info: true,
+        }
+    }
+
+    /// Restore only the balls.
+    #[must_use]
+    pub fn balls_only() -> Self {
+        Self {
+            cars: CarSelection::None,
+            balls: true,
+            match_info: false,
+        }
+    }
+
+    /// Restore only the cars of the given team.
+    #[must_use]
+    pub fn team_only(team: u32) -> Self {
+        Self {
+            cars: CarSelection::Team(team),
+            balls: false,
+            match_info: false,
+        }
+    }
+
+    /// Restore the balls and the cars of the given team.
+    #[must_use]
+    pub fn team_and_balls(team: u32) -> Self {
+        Self {
+            cars: CarSelection::Team(team),
+            balls: true,
+            match_info: false,
+        }
+    }
+}
+
+/// A captured moment of a match.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameSnapshot {
+    /// The game time at which the snapshot was taken.
+    pub game_seconds: f32,
+    /// The team of each car, used for filtering.
+    pub car_teams: Vec<u32>,
+    pub state: DesiredGameState,
+}
+
+impl GameSnapshot {
+    #[must_use]
+    pub fn capture(game_packet: &GamePacket) -> Self {
+        Self {
+            game_seconds: game_packet.match_info.seconds_elapsed,
+            car_teams: game_packet
+                .players
+                .iter()
+                .map(|player| player.team)
+                .collect(),
+            state: game_packet.into(),
+        }
+    }
+
+    /// Build a [DesiredGameState] restoring the parts of the snapshot selected
+    /// by the filter. Cars that aren't selected are left untouched.
+    #[must_use]
+    pub fn restore(&self, filter: &RestoreFilter) -> DesiredGameState {
+        let is_selected = |i: usize| match &filter.cars {
+            CarSelection::All => true,
+            CarSelection::None => false,
+            CarSelection::Team(team) => self.car_teams.get(i) == Some(team),
+            CarSelection::Indices(indices) => indices.contains(&i),
+        };
+
+        let car_states = self
+            .state
+            .car_states
+            .iter()
+            .enumerate()
+            .map(|(i, car)| {
+                if is_selected(i) {
+                    car.clone()
+                } else {
+                    DesiredCarState::default()
+                }
+            })
+            .collect();
+
+        let mut state = DesiredGameState {
+            ball_states: if filter.balls {
+                self.state.ball_states.clone()
+            } else {
+                Vec::new()
+            },
+            car_states,
+            match_info: if filter.match_info {
+                self.state.match_info.clone()
+            } else {
+                None
+            },
+            ..Default::default()
+        };
+        state.trim();
+        state
+    }
+}
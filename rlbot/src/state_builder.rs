@@ -19,11 +19,18 @@
 //! })));
 //! ```
 
+use std::{
+    f32::consts::{FRAC_PI_2, FRAC_PI_4, PI},
+    fmt,
+};
+
 use rlbot_flat::flat::{
-    DesiredBallState, DesiredCarState, DesiredGameState, DesiredMatchInfo, DesiredPhysics, Rotator,
-    RotatorPartial, Vector3, Vector3Partial,
+    ConsoleCommand, DesiredBallState, DesiredCarState, DesiredGameState, DesiredMatchInfo,
+    DesiredPhysics, Rotator, RotatorPartial, Vector3, Vector3Partial,
 };
 
+use crate::constants::CAR_REST_HEIGHT;
+
 /// Extension methods for easy construction of a [DesiredGameState].
 pub trait DesiredGameStateExt {
     fn mod_match_info(&mut self, build: impl FnOnce(&mut DesiredMatchInfo));
@@ -38,6 +45,10 @@ pub trait DesiredGameStateExt {
         &mut self,
         build: impl IntoIterator<Item = (usize, impl Fn(&mut DesiredBallState))>,
     );
+
+    fn push_console_command(&mut self, command: impl Into<ConsoleCommand>);
+
+    fn trim(&mut self);
 }
 
 #[allow(dead_code)]
@@ -88,6 +99,57 @@ impl DesiredGameStateExt for DesiredGameState {
             func(&mut self.ball_states[i]);
         }
     }
+
+    /// Add a console command to be executed by the game.
+    fn push_console_command(&mut self, command: impl Into<ConsoleCommand>) {
+        self.console_commands.push(command.into());
+    }
+
+    /// Remove trailing cars and balls that don't modify anything.
+    fn trim(&mut self) {
+        while self.car_states.last() == Some(&DesiredCarState::default()) {
+            self.car_states.pop();
+        }
+        while self.ball_states.last() == Some(&DesiredBallState::default()) {
+            self.ball_states.pop();
+        }
+    }
+}
+
+/// Commonly used console commands, for use with
+/// [DesiredGameStateExt::push_console_command].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    /// Toggle the FPS counter.
+    StatFps,
+    /// Toggle pausing the game.
+    Pause,
+    /// Set the speed of the game, where 1 is normal speed.
+    Slomo(f32),
+    /// Set the world gravity z.
+    SetGravityZ(f32),
+    /// Any other command.
+    Raw(String),
+}
+
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::StatFps => write!(f, "Stat FPS"),
+            Self::Pause => write!(f, "Pause"),
+            Self::Slomo(speed) => write!(f, "Slomo {speed}"),
+            Self::SetGravityZ(gravity_z) => write!(f, "Set WorldInfo WorldGravityZ {gravity_z}"),
+            Self::Raw(command) => write!(f, "{command}"),
+        }
+    }
+}
+
+impl From<Command> for ConsoleCommand {
+    fn from(value: Command) -> Self {
+        Self {
+            command: value.to_string(),
+        }
+    }
 }
 
 /// Extension methods for easy construction of a [DesiredMatchInfo].
@@ -109,10 +171,63 @@ impl DesiredMatchInfoExt for DesiredMatchInfo {
     }
 }
 
+/// The standard soccar kickoff spawns, named from the perspective of the team
+/// spawning there.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KickoffSpawn {
+    DiagonalRight,
+    DiagonalLeft,
+    OffCenterRight,
+    OffCenterLeft,
+    BackCenter,
+}
+
+impl KickoffSpawn {
+    pub const ALL: [Self; 5] = [
+        Self::DiagonalRight,
+        Self::DiagonalLeft,
+        Self::OffCenterRight,
+        Self::OffCenterLeft,
+        Self::BackCenter,
+    ];
+
+    /// The location of this spawn for the given team.
+    #[must_use]
+    pub fn location(self, team: u32) -> Vector3 {
+        let (x, y) = match self {
+            Self::DiagonalRight => (-2048., -2560.),
+            Self::DiagonalLeft => (2048., -2560.),
+            Self::OffCenterRight => (-256., -3840.),
+            Self::OffCenterLeft => (256., -3840.),
+            Self::BackCenter => (0., -4608.),
+        };
+        let side = if team == 0 { 1. } else { -1. };
+
+        Vector3 {
+            x: x * side,
+            y: y * side,
+            z: CAR_REST_HEIGHT,
+        }
+    }
+
+    /// The yaw of a car at this spawn for the given team.
+    #[must_use]
+    pub fn yaw(self, team: u32) -> f32 {
+        let yaw = match self {
+            Self::DiagonalRight => FRAC_PI_4,
+            Self::DiagonalLeft => 3. * FRAC_PI_4,
+            Self::OffCenterRight | Self::OffCenterLeft | Self::BackCenter => FRAC_PI_2,
+        };
+
+        if team == 0 { yaw } else { yaw - PI }
+    }
+}
+
 /// Extension methods for easy construction of a [DesiredCarState].
 pub trait DesiredCarStateExt {
     fn set_boost(&mut self, amount: f32);
     fn mod_physics(&mut self, build: impl FnOnce(&mut DesiredPhysics));
+    fn set_kickoff_spawn(&mut self, spawn: KickoffSpawn, team: u32);
 }
 
 impl DesiredCarStateExt for DesiredCarState {
@@ -125,6 +240,17 @@ impl DesiredCarStateExt for DesiredCarState {
     fn mod_physics(&mut self, build: impl FnOnce(&mut DesiredPhysics)) {
         build(self.physics.get_or_insert_default());
     }
+
+    /// Place this car at a kickoff spawn, standing still.
+    fn set_kickoff_spawn(&mut self, spawn: KickoffSpawn, team: u32) {
+        self.set_location(spawn.location(team));
+        self.set_rotation(Rotator {
+            pitch: 0.,
+            yaw: spawn.yaw(team),
+            roll: 0.,
+        });
+        self.zero_momentum();
+    }
 }
 
 /// Extension methods for easy construction of a [DesiredBallState].
@@ -157,6 +283,8 @@ pub trait DesiredPhysicsExt {
     fn set_angular_velocity_x(&mut self, x: f32);
     fn set_angular_velocity_y(&mut self, y: f32);
     fn set_angular_velocity_z(&mut self, z: f32);
+    fn zero_momentum(&mut self);
+    fn face_towards(&mut self, location: impl Into<Vector3>, target: impl Into<Vector3>);
 }
 
 macro_rules! physics_path {
@@ -241,6 +369,23 @@ macro_rules! desired_physics_ext {
             fn set_angular_velocity_z(&mut self, z: f32) {
                 physics_path!(self $p).angular_velocity.get_or_insert_default().z.get_or_insert_default().val = z;
             }
+
+            /// Set both the velocity and angular velocity to zero.
+            fn zero_momentum(&mut self) {
+                self.set_velocity(Vector3::default());
+                self.set_angular_velocity(Vector3::default());
+            }
+
+            /// Set an upright rotation, facing from `location` towards `target`
+            /// in the horizontal plane.
+            fn face_towards(&mut self, location: impl Into<Vector3>, target: impl Into<Vector3>) {
+                let (location, target) = (location.into(), target.into());
+                self.set_rotation(Rotator {
+                    pitch: 0.,
+                    yaw: f32::atan2(target.y - location.y, target.x - location.x),
+                    roll: 0.,
+                });
+            }
         }
     };
 }
@@ -0,0 +1,173 @@
+//! This module contains [diff], which reduces a [DesiredGameState] to the
+//! fields that differ from the current [GamePacket].
+//!
+//! Scripts that nudge physics every tick can send the result instead of the
+//! full state, so the game is only overridden where it actually needs to be.
+//!
+//! Example:
+//! ```ignore
+//! use rlbot::state_diff::{Tolerance, diff};
+//!
+//! let minimal = diff(&game_packet, &target, &Tolerance::default());
+//! if minimal != DesiredGameState::default() {
+//!     packet_queue.push(minimal);
+//! }
+//! ```
+
+use std::f32::consts::{PI, TAU};
+
+use rlbot_flat::flat::{
+    DesiredBallState, DesiredCarState, DesiredGameState, DesiredMatchInfo, DesiredPhysics, Float,
+    GamePacket, Physics, Rotator, RotatorPartial, Vector3, Vector3Partial,
+};
+
+use crate::state_builder::DesiredGameStateExt;
+
+/// The maximum difference per component for a field to count as unchanged.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tolerance {
+    pub location: f32,
+    /// In radians.
+    pub rotation: f32,
+    pub velocity: f32,
+    pub angular_velocity: f32,
+    pub boost: f32,
+    /// Used for gravity and game speed.
+    pub match_info: f32,
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Self {
+            location: 1.,
+            rotation: 0.01,
+            velocity: 1.,
+            angular_velocity: 0.01,
+            boost: 0.5,
+            match_info: 0.001,
+        }
+    }
+}
+
+/// Produce the minimal [DesiredGameState] that turns the state of
+/// `game_packet` into `target`. Fields already within tolerance are removed,
+/// and trailing untouched cars and balls are trimmed.
+///
+/// Cars and balls that don't exist in `game_packet` are kept as they are.
+/// Console commands are always kept.
+#[must_use]
+pub fn diff(
+    game_packet: &GamePacket,
+    target: &DesiredGameState,
+    tolerance: &Tolerance,
+) -> DesiredGameState {
+    let car_states = target
+        .car_states
+        .iter()
+        .enumerate()
+        .map(|(i, car)| match game_packet.players.get(i) {
+            Some(player) => DesiredCarState {
+                physics: car
+                    .physics
+                    .as_ref()
+                    .map(|physics| diff_physics(physics, &player.physics, tolerance))
+                    .filter(|physics| *physics != DesiredPhysics::default())
+                    .map(Box::new),
+                boost_amount: diff_float(car.boost_amount, player.boost, tolerance.boost),
+            },
+            None => car.clone(),
+        })
+        .collect();
+
+    let ball_states = target
+        .ball_states
+        .iter()
+        .enumerate()
+        .map(|(i, ball)| match game_packet.balls.get(i) {
+            Some(current) => DesiredBallState {
+                physics: Box::new(diff_physics(&ball.physics, &current.physics, tolerance)),
+            },
+            None => ball.clone(),
+        })
+        .collect();
+
+    let match_info = target
+        .match_info
+        .as_ref()
+        .map(|match_info| DesiredMatchInfo {
+            world_gravity_z: diff_float(
+                match_info.world_gravity_z,
+                game_packet.match_info.world_gravity_z,
+                tolerance.match_info,
+            ),
+            game_speed: diff_float(
+                match_info.game_speed,
+                game_packet.match_info.game_speed,
+                tolerance.match_info,
+            ),
+        })
+        .filter(|match_info| *match_info != DesiredMatchInfo::default())
+        .map(Box::new);
+
+    let mut state = DesiredGameState {
+        ball_states,
+        car_states,
+        match_info,
+        console_commands: target.console_commands.clone(),
+    };
+    state.trim();
+    state
+}
+
+fn diff_physics(
+    target: &DesiredPhysics,
+    current: &Physics,
+    tolerance: &Tolerance,
+) -> DesiredPhysics {
+    DesiredPhysics {
+        location: diff_vector(&target.location, current.location, tolerance.location),
+        rotation: diff_rotator(&target.rotation, current.rotation, tolerance.rotation),
+        velocity: diff_vector(&target.velocity, current.velocity, tolerance.velocity),
+        angular_velocity: diff_vector(
+            &target.angular_velocity,
+            current.angular_velocity,
+            tolerance.angular_velocity,
+        ),
+    }
+}
+
+fn diff_vector(
+    target: &Option<Box<Vector3Partial>>,
+    current: Vector3,
+    tolerance: f32,
+) -> Option<Box<Vector3Partial>> {
+    let target = target.as_ref()?;
+    let vector = Vector3Partial {
+        x: diff_float(target.x, current.x, tolerance),
+        y: diff_float(target.y, current.y, tolerance),
+        z: diff_float(target.z, current.z, tolerance),
+    };
+    (vector != Vector3Partial::default()).then(|| Box::new(vector))
+}
+
+fn diff_rotator(
+    target: &Option<Box<RotatorPartial>>,
+    current: Rotator,
+    tolerance: f32,
+) -> Option<Box<RotatorPartial>> {
+    let target = target.as_ref()?;
+    let rotator = RotatorPartial {
+        pitch: diff_angle(target.pitch, current.pitch, tolerance),
+        yaw: diff_angle(target.yaw, current.yaw, tolerance),
+        roll: diff_angle(target.roll, current.roll, tolerance),
+    };
+    (rotator != RotatorPartial::default()).then(|| Box::new(rotator))
+}
+
+fn diff_float(target: Option<Float>, current: f32, tolerance: f32) -> Option<Float> {
+    target.filter(|target| (target.val - current).abs() > tolerance)
+}
+
+fn diff_angle(target: Option<Float>, current: f32, tolerance: f32) -> Option<Float> {
+    target.filter(|target| ((target.val - current + PI).rem_euclid(TAU) - PI).abs() > tolerance)
+}
@@ -0,0 +1,241 @@
+//! This module contains [StatsAggregator], which consumes [GamePacket]s, live
+//! or from a recording, and produces a [MatchReport]. With the `serde` feature,
+//! reports can be exported to JSON.
+//!
+//! Example:
+//! ```ignore
+//! use rlbot::stats::StatsAggregator;
+//!
+//! let mut stats = StatsAggregator::new();
+//!
+//! // For every packet:
+//! stats.update(&game_packet);
+//!
+//! // When the match has ended:
+//! std::fs::write("report.json", stats.report().to_json()?)?;
+//! ```
+
+use rlbot_flat::{
+    flat::{AirState, GamePacket, MatchPhase},
+    glam::Vec3,
+};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    field::BACK_WALL_Y,
+    player::{DemolitionEvent, DemolitionTracker, PlayerInfoExt},
+    touches::TouchTracker,
+};
+
+/// Statistics of a single player over a match. Times are in seconds of
+/// active play.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct PlayerStats {
+    pub name: String,
+    pub player_id: i32,
+    pub team: u32,
+    /// Boost spent during active play.
+    pub boost_used: f32,
+    pub time_supersonic: f32,
+    pub time_airborne: f32,
+    pub touches: u32,
+    /// Demolitions inflicted.
+    pub demos: u32,
+    /// Times demolished.
+    pub demolished: u32,
+    pub goals: u32,
+    pub own_goals: u32,
+    pub assists: u32,
+    pub saves: u32,
+    pub shots: u32,
+    pub score: u32,
+    pub average_distance_to_ball: f32,
+    /// Time spent in the third of the field closest to the player's own goal.
+    pub time_defensive_third: f32,
+    pub time_neutral_third: f32,
+    pub time_offensive_third: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MatchReport {
+    /// Seconds of active play.
+    pub duration: f32,
+    /// The score of each team, by team index.
+    pub team_scores: Vec<u32>,
+    /// The share of possession of each team, by team index.
+    pub possession: Vec<f32>,
+    pub players: Vec<PlayerStats>,
+}
+
+#[cfg(feature = "serde")]
+impl MatchReport {
+    /// Serialize the report to pretty-printed JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the report contains non-finite numbers.
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// # Errors
+    ///
+    /// Returns an error if the JSON isn't a valid report.
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct PlayerAccumulator {
+    stats: PlayerStats,
+    last_boost: Option<f32>,
+    distance_time: f32,
+}
+
+/// Accumulates [PlayerStats] over a match.
+#[derive(Debug, Clone, Default)]
+pub struct StatsAggregator {
+    players: Vec<PlayerAccumulator>,
+    team_scores: Vec<u32>,
+    duration: f32,
+    last_seconds: Option<f32>,
+    touches: TouchTracker,
+    demolitions: DemolitionTracker,
+}
+
+impl StatsAggregator {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn update(&mut self, game_packet: &GamePacket) {
+        let now = game_packet.match_info.seconds_elapsed;
+        let is_active = game_packet.match_info.match_phase == MatchPhase::Active;
+        let dt = self
+            .last_seconds
+            .map_or(0., |last_seconds| (now - last_seconds).max(0.));
+        self.last_seconds = Some(now);
+
+        if self.players.len() < game_packet.players.len() {
+            self.players
+                .resize_with(game_packet.players.len(), Default::default);
+        }
+
+        for touch in self.touches.update(game_packet) {
+            if let Some(player) = self.players.get_mut(touch.player_index) {
+                player.stats.touches += 1;
+            }
+        }
+
+        let mut respawned = Vec::new();
+        for event in self.demolitions.update(game_packet) {
+            match event {
+                DemolitionEvent::Demolished { player_index } => {
+                    if let Some(player) = self.players.get_mut(player_index) {
+                        player.stats.demolished += 1;
+                    }
+                }
+                DemolitionEvent::Respawned { player_index } => respawned.push(player_index),
+            }
+        }
+
+        self.team_scores = game_packet.teams.iter().map(|team| team.score).collect();
+
+        let ball_location = game_packet
+            .balls
+            .first()
+            .map(|ball| Vec3::from(ball.physics.location));
+
+        if is_active {
+            self.duration += dt;
+        }
+
+        for (player_index, (accumulator, player)) in self
+            .players
+            .iter_mut()
+            .zip(&game_packet.players)
+            .enumerate()
+        {
+            let stats = &mut accumulator.stats;
+            stats.name.clone_from(&player.name);
+            stats.player_id = player.player_id;
+            stats.team = player.team;
+
+            let score_info = &player.score_info;
+            stats.goals = score_info.goals;
+            stats.own_goals = score_info.own_goals;
+            stats.assists = score_info.assists;
+            stats.saves = score_info.saves;
+            stats.shots = score_info.shots;
+            stats.demos = score_info.demolitions;
+            stats.score = score_info.score;
+
+            // Boost lost to demolitions, respawns, and resets isn't used
+            let boost_can_be_used =
+                is_active && !player.is_demolished() && !respawned.contains(&player_index);
+            if let Some(last_boost) = accumulator.last_boost
+                && boost_can_be_used
+            {
+                stats.boost_used += (last_boost - player.boost).max(0.);
+            }
+            accumulator.last_boost = Some(player.boost);
+
+            if !is_active || player.is_demolished() {
+                continue;
+            }
+
+            if player.is_supersonic {
+                stats.time_supersonic += dt;
+            }
+            if player.air_state != AirState::OnGround {
+                stats.time_airborne += dt;
+            }
+
+            if let Some(ball_location) = ball_location {
+                let distance = player.location().distance(ball_location);
+                let total_time = accumulator.distance_time + dt;
+                if total_time > 0. {
+                    stats.average_distance_to_ball +=
+                        (distance - stats.average_distance_to_ball) * dt / total_time;
+                }
+                accumulator.distance_time = total_time;
+            }
+
+            // Positive y is towards the opponent's goal
+            let y = if player.team == 0 {
+                player.physics.location.y
+            } else {
+                -player.physics.location.y
+            };
+            if y < -BACK_WALL_Y / 3. {
+                stats.time_defensive_third += dt;
+            } else if y > BACK_WALL_Y / 3. {
+                stats.time_offensive_third += dt;
+            } else {
+                stats.time_neutral_third += dt;
+            }
+        }
+    }
+
+    /// The statistics of everything seen so far.
+    #[must_use]
+    pub fn report(&self) -> MatchReport {
+        MatchReport {
+            duration: self.duration,
+            team_scores: self.team_scores.clone(),
+            possession: (0..2)
+                .map(|team| self.touches.possession_share(team))
+                .collect(),
+            players: self
+                .players
+                .iter()
+                .map(|accumulator| accumulator.stats.clone())
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,178 @@
+//! This module contains [TouchTracker], which turns the `latest_touch` of
+//! each player into touch events and keeps a possession estimate per team.
+//!
+//! Example:
+//! ```ignore
+//! use rlbot::touches::TouchTracker;
+//!
+//! let mut touches = TouchTracker::new();
+//!
+//! // In tick:
+//! for touch in touches.update(&game_packet) {
+//!     println!(
+//!         "Player {} hit the ball, changing its speed by {:.0}",
+//!         touch.player_index,
+//!         touch.speed_change()
+//!     );
+//! }
+//! println!("Blue possession: {:.0}%", touches.possession_share(0) * 100.);
+//! ```
+
+use rlbot_flat::{
+    flat::{GamePacket, MatchPhase},
+    glam::Vec3,
+};
+
+/// A touch of a ball by a player.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TouchEvent {
+    pub player_index: usize,
+    pub team: u32,
+    pub ball_index: usize,
+    /// The game time of the touch.
+    pub game_seconds: f32,
+    /// Where the contact happened.
+    pub location: Vec3,
+    /// The direction the ball was hit in.
+    pub normal: Vec3,
+    /// The speed of the ball in the packet before the touch.
+    pub ball_speed_before: f32,
+    /// The speed of the ball in the packet after the touch.
+    pub ball_speed_after: f32,
+}
+
+impl TouchEvent {
+    #[must_use]
+    pub fn speed_change(&self) -> f32 {
+        self.ball_speed_after - self.ball_speed_before
+    }
+}
+
+/// Watches consecutive packets for new touches.
+///
+/// Possession belongs to the team that touched a ball last, and is reset at
+/// every kickoff.
+#[derive(Debug, Clone, Default)]
+pub struct TouchTracker {
+    latest_touches: Vec<Option<f32>>,
+    ball_speeds: Vec<f32>,
+    last_touch: Option<TouchEvent>,
+    possession: Option<u32>,
+    possession_time: [f32; 2],
+    last_seconds: Option<f32>,
+}
+
+impl TouchTracker {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Update the tracker with a new packet. Returns the touches that
+    /// happened since the previous packet, in chronological order.
+    pub fn update(&mut self, game_packet: &GamePacket) -> Vec<TouchEvent> {
+        let now = game_packet.match_info.seconds_elapsed;
+
+        if game_packet.match_info.match_phase == MatchPhase::Active
+            && let (Some(team), Some(last_seconds)) = (self.possession, self.last_seconds)
+            && let Some(time) = self.possession_time.get_mut(team as usize)
+        {
+            *time += (now - last_seconds).max(0.);
+        }
+        self.last_seconds = Some(now);
+
+        if game_packet.match_info.match_phase == MatchPhase::Kickoff {
+            self.possession = None;
+        }
+
+        let latest_touches: Vec<_> = game_packet
+            .players
+            .iter()
+            .map(|player| player.latest_touch.as_ref().map(|touch| touch.game_seconds))
+            .collect();
+
+        let mut events: Vec<_> = game_packet
+            .players
+            .iter()
+            .enumerate()
+            .filter_map(|(player_index, player)| {
+                let touch = player.latest_touch.as_ref()?;
+                // A player we haven't seen before has no previous touch to
+                // compare with, so their current one is old news.
+                let previous = self.latest_touches.get(player_index)?;
+                if *previous == Some(touch.game_seconds) {
+                    return None;
+                }
+
+                let ball_index = touch.ball_index as usize;
+                let ball_speed_after = game_packet
+                    .balls
+                    .get(ball_index)
+                    .map_or(0., |ball| Vec3::from(ball.physics.velocity).length());
+
+                Some(TouchEvent {
+                    player_index,
+                    team: player.team,
+                    ball_index,
+                    game_seconds: touch.game_seconds,
+                    location: touch.location.into(),
+                    normal: touch.normal.into(),
+                    ball_speed_before: self
+                        .ball_speeds
+                        .get(ball_index)
+                        .copied()
+                        .unwrap_or(ball_speed_after),
+                    ball_speed_after,
+                })
+            })
+            .collect();
+
+        events.sort_by(|a, b| a.game_seconds.total_cmp(&b.game_seconds));
+
+        if let Some(&last) = events.last() {
+            self.last_touch = Some(last);
+            self.possession = Some(last.team);
+        }
+
+        self.latest_touches = latest_touches;
+        self.ball_speeds = game_packet
+            .balls
+            .iter()
+            .map(|ball| Vec3::from(ball.physics.velocity).length())
+            .collect();
+
+        events
+    }
+
+    /// The most recent touch seen by the tracker.
+    #[must_use]
+    pub fn last_touch(&self) -> Option<&TouchEvent> {
+        self.last_touch.as_ref()
+    }
+
+    /// The team currently in possession of the ball.
+    #[must_use]
+    pub fn possession(&self) -> Option<u32> {
+        self.possession
+    }
+
+    /// Seconds of active play the team has been in possession.
+    #[must_use]
+    pub fn possession_time(&self, team: u32) -> f32 {
+        self.possession_time
+            .get(team as usize)
+            .copied()
+            .unwrap_or(0.)
+    }
+
+    /// The fraction of possession time that belongs to the team, from 0 to 1.
+    #[must_use]
+    pub fn possession_share(&self, team: u32) -> f32 {
+        let total: f32 = self.possession_time.iter().sum();
+        if total == 0. {
+            0.
+        } else {
+            self.possession_time(team) / total
+        }
+    }
+}
@@ -1,6 +1,8 @@
-use std::{env, mem};
+use std::{env, fmt, mem, sync::Arc};
 
-use rlbot_flat::flat::InterfaceMessage;
+use rlbot_flat::flat::{GamePacket, InterfaceMessage, MatchConfiguration};
+
+use crate::validation::validate;
 
 pub struct AgentEnvironment {
     /// Will fallback to 127.0.0.1:23234
@@ -33,6 +35,12 @@ impl AgentEnvironment {
 /// A queue of packets to be sent to RLBotServer
 pub struct PacketQueue {
     pub(crate) internal_queue: Vec<InterfaceMessage>,
+    validation: Option<Validation>,
+}
+
+struct Validation {
+    match_configuration: Arc<MatchConfiguration>,
+    game_packet: Option<GamePacket>,
 }
 
 impl Default for PacketQueue {
@@ -46,6 +54,31 @@ impl PacketQueue {
     pub fn new(capacity: usize) -> Self {
         Self {
             internal_queue: Vec::with_capacity(capacity),
+            validation: None,
+        }
+    }
+
+    /// Validate every [DesiredGameState](rlbot_flat::flat::DesiredGameState)
+    /// against the latest [GamePacket] before it's sent, printing any issues
+    /// to stderr. States pushed before the first [GamePacket] arrives aren't
+    /// validated.
+    ///
+    /// This clones every [GamePacket], so it's meant for debugging only.
+    /// See [validate] for the checks that are run.
+    pub fn enable_validation(&mut self, match_configuration: impl Into<Arc<MatchConfiguration>>) {
+        self.validation = Some(Validation {
+            match_configuration: match_configuration.into(),
+            game_packet: None,
+        });
+    }
+
+    pub fn disable_validation(&mut self) {
+        self.validation = None;
+    }
+
+    pub(crate) fn observe_game_packet(&mut self, game_packet: &GamePacket) {
+        if let Some(validation) = &mut self.validation {
+            validation.game_packet = Some(game_packet.clone());
         }
     }
 
@@ -54,6 +87,26 @@ impl PacketQueue {
     }
 
     pub(crate) fn empty(&mut self) -> Vec<InterfaceMessage> {
+        if let Some(Validation {
+            match_configuration,
+            game_packet: Some(game_packet),
+        }) = &self.validation
+        {
+            for message in &self.internal_queue {
+                if let InterfaceMessage::DesiredGameState(state) = message {
+                    for issue in validate(state, game_packet, match_configuration) {
+                        log(issue);
+                    }
+                }
+            }
+        }
+
         mem::take(&mut self.internal_queue)
     }
 }
+
+/// Print a diagnostic message, like a validation issue or a latency spike, to
+/// stderr.
+pub(crate) fn log(message: impl fmt::Display) {
+    eprintln!("{message}");
+}
@@ -0,0 +1,347 @@
+//! This module contains [validate], which checks a [DesiredGameState] for
+//! mistakes that core would otherwise silently ignore.
+//!
+//! Validation can also be run automatically on every state sent through a
+//! [PacketQueue](crate::util::PacketQueue) with
+//! [enable_validation](crate::util::PacketQueue::enable_validation).
+//!
+//! Example:
+//! ```ignore
+//! use rlbot::validation::validate;
+//!
+//! for issue in validate(&state, &game_packet, &match_configuration) {
+//!     eprintln!("{issue}");
+//! }
+//! ```
+
+use std::fmt;
+
+use rlbot_flat::flat::{
+    DesiredGameState, DesiredPhysics, Float, GameMode, GamePacket, MatchConfiguration,
+};
+
+use crate::constants::MAX_CAR_SPEED;
+
+/// Slightly larger than the standard soccar arena, including the goals.
+/// Only soccar-sized arenas are supported: the smaller Hoops arena is checked
+/// loosely, and locations aren't checked at all in Dropshot, where the arena
+/// is wider. Non-standard maps might not fit.
+const SOCCAR_ARENA_EXTENT: [f32; 3] = [4300., 6100., 2100.];
+
+const MAX_CAR_ANGULAR_SPEED: f32 = 5.5;
+const MAX_BALL_SPEED: f32 = 6000.;
+const MAX_BALL_ANGULAR_SPEED: f32 = 6.;
+const MAX_BOOST: f32 = 100.;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// Core will apply the state, but probably not the way it was intended.
+    Warning,
+    /// Core will ignore the state, or part of it.
+    Error,
+}
+
+/// The part of a [DesiredGameState] an issue was found in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IssueTarget {
+    State,
+    Car(usize),
+    Ball(usize),
+    MatchInfo,
+    ConsoleCommand(usize),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum IssueKind {
+    /// `MatchConfiguration.enable_state_setting` is false.
+    StateSettingDisabled,
+    /// The index is past the number of cars or balls in the [GamePacket].
+    IndexOutOfRange {
+        len: usize,
+    },
+    /// The field is NaN or infinite.
+    NotFinite {
+        field: &'static str,
+    },
+    /// The location is outside of the soccar arena, see [validate].
+    OutsideArena {
+        x: f32,
+        y: f32,
+        z: f32,
+    },
+    /// The value will be clamped by the game.
+    ExceedsLimit {
+        field: &'static str,
+        value: f32,
+        limit: f32,
+    },
+    /// The value is negative where it can't be.
+    Negative {
+        field: &'static str,
+        value: f32,
+    },
+    EmptyConsoleCommand,
+}
+
+/// A problem found by [validate].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationIssue {
+    pub severity: Severity,
+    pub target: IssueTarget,
+    pub kind: IssueKind,
+}
+
+impl fmt::Display for IssueTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::State => write!(f, "state"),
+            Self::Car(i) => write!(f, "car {i}"),
+            Self::Ball(i) => write!(f, "ball {i}"),
+            Self::MatchInfo => write!(f, "match info"),
+            Self::ConsoleCommand(i) => write!(f, "console command {i}"),
+        }
+    }
+}
+
+impl fmt::Display for IssueKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::StateSettingDisabled => {
+                write!(f, "state setting is disabled in the match configuration")
+            }
+            Self::IndexOutOfRange { len } => {
+                write!(f, "index is out of range, there are only {len}")
+            }
+            Self::NotFinite { field } => write!(f, "{field} is not finite"),
+            Self::OutsideArena { x, y, z } => {
+                write!(
+                    f,
+                    "location ({x:.0}, {y:.0}, {z:.0}) is outside of the arena"
+                )
+            }
+            Self::ExceedsLimit {
+                field,
+                value,
+                limit,
+            } => write!(f, "{field} of {value:.2} exceeds the limit of {limit:.2}"),
+            Self::Negative { field, value } => write!(f, "{field} of {value:.2} is negative"),
+            Self::EmptyConsoleCommand => write!(f, "console command is empty"),
+        }
+    }
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        write!(
+            f,
+            "State setting {severity} in {}: {}",
+            self.target, self.kind
+        )
+    }
+}
+
+/// Check a [DesiredGameState] against the current match. Returns all issues
+/// found, errors first.
+///
+/// Locations are checked against the size of the standard soccar arena,
+/// except in Dropshot.
+#[must_use]
+pub fn validate(
+    state: &DesiredGameState,
+    game_packet: &GamePacket,
+    match_configuration: &MatchConfiguration,
+) -> Vec<ValidationIssue> {
+    let mut issues = Vec::new();
+    let arena_extent =
+        (match_configuration.game_mode != GameMode::Dropshot).then_some(SOCCAR_ARENA_EXTENT);
+
+    if !match_configuration.enable_state_setting {
+        issues.push(ValidationIssue {
+            severity: Severity::Error,
+            target: IssueTarget::State,
+            kind: IssueKind::StateSettingDisabled,
+        });
+    }
+
+    for (i, car) in state.car_states.iter().enumerate() {
+        let mut checker = Checker::new(&mut issues, IssueTarget::Car(i));
+
+        if i >= game_packet.players.len() && *car != Default::default() {
+            checker.error(IssueKind::IndexOutOfRange {
+                len: game_packet.players.len(),
+            });
+        }
+
+        if let Some(physics) = &car.physics {
+            checker.physics(physics, arena_extent, MAX_CAR_SPEED, MAX_CAR_ANGULAR_SPEED);
+        }
+
+        if let Some(boost) = car.boost_amount {
+            checker.finite("boost", boost.val);
+            checker.non_negative("boost", boost.val);
+            checker.limit("boost", boost.val, MAX_BOOST);
+        }
+    }
+
+    for (i, ball) in state.ball_states.iter().enumerate() {
+        let mut checker = Checker::new(&mut issues, IssueTarget::Ball(i));
+
+        if i >= game_packet.balls.len() && *ball != Default::default() {
+            checker.error(IssueKind::IndexOutOfRange {
+                len: game_packet.balls.len(),
+            });
+        }
+
+        checker.physics(
+            &ball.physics,
+            arena_extent,
+            MAX_BALL_SPEED,
+            MAX_BALL_ANGULAR_SPEED,
+        );
+    }
+
+    if let Some(match_info) = &state.match_info {
+        let mut checker = Checker::new(&mut issues, IssueTarget::MatchInfo);
+
+        if let Some(gravity) = match_info.world_gravity_z {
+            checker.finite("world gravity", gravity.val);
+        }
+
+        if let Some(game_speed) = match_info.game_speed {
+            checker.finite("game speed", game_speed.val);
+            checker.non_negative("game speed", game_speed.val);
+        }
+    }
+
+    for (i, command) in state.console_commands.iter().enumerate() {
+        if command.command.trim().is_empty() {
+            issues.push(ValidationIssue {
+                severity: Severity::Warning,
+                target: IssueTarget::ConsoleCommand(i),
+                kind: IssueKind::EmptyConsoleCommand,
+            });
+        }
+    }
+
+    issues.sort_by_key(|issue| std::cmp::Reverse(issue.severity));
+    issues
+}
+
+struct Checker<'a> {
+    issues: &'a mut Vec<ValidationIssue>,
+    target: IssueTarget,
+}
+
+impl<'a> Checker<'a> {
+    fn new(issues: &'a mut Vec<ValidationIssue>, target: IssueTarget) -> Self {
+        Self { issues, target }
+    }
+
+    fn push(&mut self, severity: Severity, kind: IssueKind) {
+        self.issues.push(ValidationIssue {
+            severity,
+            target: self.target,
+            kind,
+        });
+    }
+
+    fn error(&mut self, kind: IssueKind) {
+        self.push(Severity::Error, kind);
+    }
+
+    fn finite(&mut self, field: &'static str, value: f32) {
+        if !value.is_finite() {
+            self.error(IssueKind::NotFinite { field });
+        }
+    }
+
+    fn non_negative(&mut self, field: &'static str, value: f32) {
+        if value < 0. {
+            self.push(Severity::Warning, IssueKind::Negative { field, value });
+        }
+    }
+
+    fn limit(&mut self, field: &'static str, value: f32, limit: f32) {
+        if value > limit {
+            self.push(
+                Severity::Warning,
+                IssueKind::ExceedsLimit {
+                    field,
+                    value,
+                    limit,
+                },
+            );
+        }
+    }
+
+    fn physics(
+        &mut self,
+        physics: &DesiredPhysics,
+        arena_extent: Option<[f32; 3]>,
+        max_speed: f32,
+        max_angular_speed: f32,
+    ) {
+        if let Some(location) = &physics.location {
+            let components = [location.x, location.y, location.z];
+            self.finite_components("location", components);
+
+            if let Some(arena_extent) = arena_extent
+                && components
+                    .iter()
+                    .zip(arena_extent)
+                    .any(|(c, extent)| c.is_some_and(|c| c.val.abs() > extent))
+            {
+                self.error(IssueKind::OutsideArena {
+                    x: value_or_nan(location.x),
+                    y: value_or_nan(location.y),
+                    z: value_or_nan(location.z),
+                });
+            }
+        }
+
+        if let Some(rotation) = &physics.rotation {
+            self.finite_components("rotation", [rotation.pitch, rotation.yaw, rotation.roll]);
+        }
+
+        if let Some(velocity) = &physics.velocity {
+            let components = [velocity.x, velocity.y, velocity.z];
+            self.finite_components("velocity", components);
+            self.limit("speed", magnitude(components), max_speed);
+        }
+
+        if let Some(angular_velocity) = &physics.angular_velocity {
+            let components = [angular_velocity.x, angular_velocity.y, angular_velocity.z];
+            self.finite_components("angular velocity", components);
+            self.limit("angular speed", magnitude(components), max_angular_speed);
+        }
+    }
+
+    fn finite_components(&mut self, field: &'static str, components: [Option<Float>; 3]) {
+        if components
+            .iter()
+            .flatten()
+            .any(|component| !component.val.is_finite())
+        {
+            self.error(IssueKind::NotFinite { field });
+        }
+    }
+}
+
+fn value_or_nan(component: Option<Float>) -> f32 {
+    component.map_or(f32::NAN, |component| component.val)
+}
+
+/// The magnitude of the set components. Unset components are left as they
+/// are by core, so they're not counted.
+fn magnitude(components: [Option<Float>; 3]) -> f32 {
+    components
+        .iter()
+        .flatten()
+        .map(|component| component.val * component.val)
+        .sum::<f32>()
+        .sqrt()
+}
@@ -0,0 +1,81 @@
+//! Checks [BallSimulator] against ball predictions recorded from core in
+//! `fixtures/`.
+//!
+//! Every fixture holds the starting state of the ball and the predicted
+//! location of the ball over time, as `[game_seconds, x, y, z]` slices.
+//! Record them with the `record_fixture` example:
+//! - `ball_roll.json`: the ball rolling along the floor at 500 uu/s.
+//! - `ball_wall_bounce.json`: the ball flying into a side wall.
+//!
+//! The tests are ignored until the fixtures are recorded.
+
+#![cfg(feature = "glam")]
+
+use std::fs;
+
+use rlbot::{
+    ball_sim::{BallSimulator, PredictionError, compare},
+    flat::{BallPrediction, Physics, PredictionSlice, Vector3},
+};
+use serde::Deserialize;
+
+/// The furthest the simulated ball may be from the fixture at any time.
+const MAX_DISTANCE: f32 = 25.;
+
+#[derive(Deserialize)]
+struct Fixture {
+    game_seconds: f32,
+    location: [f32; 3],
+    velocity: [f32; 3],
+    angular_velocity: [f32; 3],
+    slices: Vec<[f32; 4]>,
+}
+
+fn vector([x, y, z]: [f32; 3]) -> Vector3 {
+    Vector3 { x, y, z }
+}
+
+fn check(name: &str) -> PredictionError {
+    let path = format!("{}/tests/fixtures/{name}", env!("CARGO_MANIFEST_DIR"));
+    let fixture = fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("{path} has to be recorded from core: {e}"));
+    let fixture: Fixture = serde_json::from_str(&fixture).unwrap();
+    let ball = Physics {
+        location: vector(fixture.location),
+        velocity: vector(fixture.velocity),
+        angular_velocity: vector(fixture.angular_velocity),
+        ..Default::default()
+    };
+    let reference = BallPrediction {
+        slices: fixture
+            .slices
+            .iter()
+            .map(|&[game_seconds, x, y, z]| PredictionSlice {
+                game_seconds,
+                physics: Physics {
+                    location: Vector3 { x, y, z },
+                    ..Default::default()
+                },
+            })
+            .collect(),
+    };
+
+    let simulated = BallSimulator::default().predict_like_core(&ball, fixture.game_seconds);
+    compare(&simulated.slices, &reference).unwrap()
+}
+
+#[test]
+#[ignore = "needs ball_roll.json recorded from core"]
+fn roll() {
+    let error = check("ball_roll.json");
+    assert!(error.samples > 300, "{error:?}");
+    assert!(error.max_distance < MAX_DISTANCE, "{error:?}");
+}
+
+#[test]
+#[ignore = "needs ball_wall_bounce.json recorded from core"]
+fn wall_bounce() {
+    let error = check("ball_wall_bounce.json");
+    assert!(error.samples > 200, "{error:?}");
+    assert!(error.max_distance < MAX_DISTANCE, "{error:?}");
+}
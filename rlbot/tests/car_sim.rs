@@ -0,0 +1,160 @@
+//! Checks [CarSimulator] against packets recorded from core in `fixtures/`.
+//!
+//! Every fixture holds the packets of a single car, with the inputs it was
+//! given. Record them with the `record_fixture` example:
+//! - `car_drive_boost_jump.json`: a car driving, boosting and jumping.
+//!
+//! The recording tests are ignored until the fixtures are recorded.
+
+#![cfg(feature = "glam")]
+
+use std::fs;
+
+use rlbot::{
+    car_sim::{CarSimulator, CarState},
+    controller::ActionSequence,
+    flat::{
+        AirState, ControllerState, GamePacket, MatchInfo, Physics, PlayerInfo, Rotator, Vector3,
+    },
+};
+use serde::Deserialize;
+
+/// The furthest the car may be from the next packet after simulating it from
+/// the previous one.
+const MAX_STEP_DISTANCE: f32 = 1.;
+/// The furthest the car may be from any packet when the whole recording is
+/// simulated from the first packet.
+const MAX_REPLAY_DISTANCE: f32 = 25.;
+
+#[derive(Deserialize)]
+struct Fixture {
+    packets: Vec<Packet>,
+}
+
+#[derive(Deserialize)]
+struct Packet {
+    seconds: f32,
+    location: [f32; 3],
+    rotation: [f32; 3],
+    velocity: [f32; 3],
+    angular_velocity: [f32; 3],
+    boost: f32,
+    air_state: String,
+    has_jumped: bool,
+    input: Input,
+}
+
+#[derive(Deserialize)]
+struct Input {
+    throttle: f32,
+    steer: f32,
+    pitch: f32,
+    yaw: f32,
+    roll: f32,
+    boost: bool,
+    jump: bool,
+    handbrake: bool,
+}
+
+fn vector([x, y, z]: [f32; 3]) -> Vector3 {
+    Vector3 { x, y, z }
+}
+
+fn game_packet(packet: &Packet) -> GamePacket {
+    let air_state = match packet.air_state.as_str() {
+        "OnGround" => AirState::OnGround,
+        "Jumping" => AirState::Jumping,
+        "DoubleJumping" => AirState::DoubleJumping,
+        "Dodging" => AirState::Dodging,
+        _ => AirState::InAir,
+    };
+
+    GamePacket {
+        players: vec![PlayerInfo {
+            physics: Physics {
+                location: vector(packet.location),
+                rotation: Rotator {
+                    pitch: packet.rotation[0],
+                    yaw: packet.rotation[1],
+                    roll: packet.rotation[2],
+                },
+                velocity: vector(packet.velocity),
+                angular_velocity: vector(packet.angular_velocity),
+            },
+            boost: packet.boost,
+            air_state,
+            has_jumped: packet.has_jumped,
+            last_input: ControllerState {
+                throttle: packet.input.throttle,
+                steer: packet.input.steer,
+                pitch: packet.input.pitch,
+                yaw: packet.input.yaw,
+                roll: packet.input.roll,
+                jump: packet.input.jump,
+                boost: packet.input.boost,
+                handbrake: packet.input.handbrake,
+                ..Default::default()
+            },
+            ..Default::default()
+        }],
+        match_info: Box::new(MatchInfo {
+            seconds_elapsed: packet.seconds,
+            ..Default::default()
+        }),
+        ..Default::default()
+    }
+}
+
+#[test]
+#[ignore = "needs car_drive_boost_jump.json recorded from core"]
+fn drive_boost_jump() {
+    let path = format!(
+        "{}/tests/fixtures/car_drive_boost_jump.json",
+        env!("CARGO_MANIFEST_DIR")
+    );
+    let fixture = fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("{path} has to be recorded from core: {e}"));
+    let fixture: Fixture = serde_json::from_str(&fixture).unwrap();
+    let recording: Vec<_> = fixture.packets.iter().map(game_packet).collect();
+
+    let error = CarSimulator::default()
+        .error_against(&recording, 0)
+        .unwrap();
+    assert_eq!(error.samples, recording.len() - 1);
+    assert!(error.max_distance < MAX_STEP_DISTANCE, "{error:?}");
+
+    let simulator = CarSimulator::default();
+    let mut car = CarState::from_player(&recording[0].players[0]);
+    for pair in recording.windows(2) {
+        let dt = pair[1].match_info.seconds_elapsed - pair[0].match_info.seconds_elapsed;
+        let expected = &pair[1].players[0];
+        simulator.step(&mut car, &expected.last_input, dt);
+
+        let distance = car.location.distance(expected.physics.location.into());
+        assert!(
+            distance < MAX_REPLAY_DISTANCE,
+            "{distance} away at {}",
+            pair[1].match_info.seconds_elapsed,
+        );
+    }
+}
+
+#[test]
+fn sequence_needs_positive_dt() {
+    let simulator = CarSimulator::default();
+    let sequence = ActionSequence::new().then_seconds(
+        ControllerState {
+            throttle: 1.,
+            ..Default::default()
+        },
+        0.5,
+    );
+
+    for dt in [0., -1. / 120., f32::NAN] {
+        let states = simulator.simulate_sequence(CarState::default(), sequence.clone(), dt);
+        assert!(states.is_empty(), "{dt}");
+    }
+
+    let states = simulator.simulate_sequence(CarState::default(), sequence, 1. / 120.);
+    assert!((59..=61).contains(&states.len()), "{}", states.len());
+}
@@ -0,0 +1,292 @@
+//! Round-trip tests for [Mirror] on the standard soccar field.
+
+use std::f32::consts::PI;
+
+use rlbot::{
+    flat::{
+        BallPrediction, BoostPad, BoostPadState, DesiredBallState, DesiredCarState,
+        DesiredGameState, DesiredMatchInfo, DesiredPhysics, FieldInfo, GamePacket, GoalInfo,
+        Physics, PredictionSlice, Rotator, RotatorPartial, Vector3, Vector3Partial,
+    },
+    mirror::Mirror,
+};
+
+/// The boost pads of the standard soccar field, in the order core sends them.
+/// Note that one pad is at y 3310 while its counterpart is at y -3308.
+const SOCCAR_PADS: [(f32, f32, bool); 34] = [
+    (0., -4240., false),
+    (-1792., -4184., false),
+    (1792., -4184., false),
+    (-3072., -4096., true),
+    (3072., -4096., true),
+    (-940., -3308., false),
+    (940., -3308., false),
+    (0., -2816., false),
+    (-3584., -2484., false),
+    (3584., -2484., false),
+    (-1788., -2300., false),
+    (1788., -2300., false),
+    (-2048., -1036., false),
+    (0., -1024., false),
+    (2048., -1036., false),
+    (-3584., 0., true),
+    (-1024., 0., false),
+    (1024., 0., false),
+    (3584., 0., true),
+    (-2048., 1036., false),
+    (0., 1024., false),
+    (2048., 1036., false),
+    (-1788., 2300., false),
+    (1788., 2300., false),
+    (-3584., 2484., false),
+    (3584., 2484., false),
+    (0., 2816., false),
+    (-940., 3310., false),
+    (940., 3308., false),
+    (-3072., 4096., true),
+    (3072., 4096., true),
+    (-1792., 4184., false),
+    (1792., 4184., false),
+    (0., 4240., false),
+];
+
+fn soccar_field_info() -> FieldInfo {
+    FieldInfo {
+        boost_pads: SOCCAR_PADS
+            .iter()
+            .map(|&(x, y, is_full_boost)| BoostPad {
+                location: Vector3 {
+                    x,
+                    y,
+                    z: if is_full_boost { 73. } else { 70. },
+                },
+                is_full_boost,
+            })
+            .collect(),
+        goals: vec![
+            GoalInfo {
+                team_num: 0,
+                location: Vector3 {
+                    x: 0.,
+                    y: -5120.,
+                    z: 321.3875,
+                },
+                direction: Vector3 {
+                    x: 0.,
+                    y: 1.,
+                    z: 0.,
+                },
+                width: 1785.51,
+                height: 642.775,
+            },
+            GoalInfo {
+                team_num: 1,
+                location: Vector3 {
+                    x: 0.,
+                    y: 5120.,
+                    z: 321.3875,
+                },
+                direction: Vector3 {
+                    x: 0.,
+                    y: -1.,
+                    z: 0.,
+                },
+                width: 1785.51,
+                height: 642.775,
+            },
+        ],
+    }
+}
+
+fn physics(x: f32, y: f32, z: f32) -> Physics {
+    Physics {
+        location: Vector3 { x, y, z },
+        rotation: Rotator {
+            pitch: 0.25,
+            yaw: 0.,
+            roll: -0.5,
+        },
+        velocity: Vector3 {
+            x: 300.,
+            y: -700.,
+            z: 50.,
+        },
+        angular_velocity: Vector3 {
+            x: 1.,
+            y: -2.,
+            z: 3.,
+        },
+    }
+}
+
+#[test]
+fn field_info_round_trip() {
+    let field_info = soccar_field_info();
+    let mirrored = field_info.mirrored();
+
+    assert_ne!(mirrored, field_info);
+    assert_eq!(mirrored.mirrored(), field_info);
+
+    // Goals keep their team and swap sides, still facing into the field
+    for (goal, original) in mirrored.goals.iter().zip(&field_info.goals) {
+        let other = &field_info.goals[1 - original.team_num as usize];
+        assert_eq!(goal.team_num, original.team_num);
+        assert_eq!(goal.location, other.location);
+        assert_eq!(goal.direction, other.direction);
+    }
+
+    // Reversing the order maps every pad onto its counterpart
+    for (pad, original) in mirrored.boost_pads.iter().zip(&field_info.boost_pads) {
+        let dx = pad.location.x - original.location.x;
+        let dy = pad.location.y - original.location.y;
+        assert!(dx.hypot(dy) <= 2., "{pad:?} isn't at {original:?}");
+        assert_eq!(pad.location.z, original.location.z);
+        assert_eq!(pad.is_full_boost, original.is_full_boost);
+    }
+}
+
+#[test]
+fn boost_pad_indices_match_field_info() {
+    let field_info = soccar_field_info();
+    // Only the pads with negative x and y are taken
+    let game_packet = GamePacket {
+        boost_pads: field_info
+            .boost_pads
+            .iter()
+            .enumerate()
+            .map(|(i, pad)| BoostPadState {
+                is_active: pad.location.x >= 0. || pad.location.y >= 0.,
+                timer: i as f32,
+            })
+            .collect(),
+        ..Default::default()
+    };
+
+    let mirrored_field_info = field_info.mirrored();
+    let mirrored_packet = game_packet.mirrored();
+    assert_eq!(mirrored_packet.mirrored(), game_packet);
+
+    for (pad, state) in mirrored_field_info
+        .boost_pads
+        .iter()
+        .zip(&mirrored_packet.boost_pads)
+    {
+        // The state at every index still belongs to the pad at that index
+        let original_index = state.timer as usize;
+        let original = &field_info.boost_pads[original_index];
+        assert_eq!(pad.location, original.location.mirrored());
+        assert_eq!(state, &game_packet.boost_pads[original_index]);
+
+        // Which, after mirroring, are the pads with positive x and y
+        assert_eq!(
+            state.is_active,
+            pad.location.x <= 0. || pad.location.y <= 0.
+        );
+    }
+}
+
+#[test]
+fn ball_prediction_round_trip() {
+    let ball_prediction = BallPrediction {
+        slices: (0..120)
+            .map(|i| PredictionSlice {
+                game_seconds: 10. + i as f32 / 120.,
+                physics: physics(i as f32 * 10., -2000. + i as f32 * 5., 93.),
+            })
+            .collect(),
+    };
+    let mirrored = ball_prediction.mirrored();
+
+    for (slice, original) in mirrored.slices.iter().zip(&ball_prediction.slices) {
+        assert_eq!(slice.game_seconds, original.game_seconds);
+        assert_eq!(slice.physics.location.x, -original.physics.location.x);
+        assert_eq!(slice.physics.location.y, -original.physics.location.y);
+        assert_eq!(slice.physics.location.z, original.physics.location.z);
+        assert_eq!(slice.physics.rotation.yaw, -PI);
+    }
+
+    assert_eq!(mirrored.mirrored(), ball_prediction);
+}
+
+#[test]
+fn desired_game_state_round_trip() {
+    let partial = |x: Option<f32>, y: Option<f32>, z: Option<f32>| {
+        Some(Box::new(Vector3Partial {
+            x: x.map(Into::into),
+            y: y.map(Into::into),
+            z: z.map(Into::into),
+        }))
+    };
+
+    let desired_game_state = DesiredGameState {
+        ball_states: vec![
+            DesiredBallState {
+                physics: Box::new(physics(100., 200., 300.).into()),
+            },
+            DesiredBallState {
+                physics: Box::new(DesiredPhysics {
+                    location: partial(Some(1000.), None, Some(500.)),
+                    velocity: partial(None, Some(-1500.), None),
+                    ..Default::default()
+                }),
+            },
+        ],
+        car_states: vec![
+            DesiredCarState {
+                physics: Some(Box::new(DesiredPhysics {
+                    location: partial(None, None, Some(17.)),
+                    rotation: Some(Box::new(RotatorPartial {
+                        pitch: Some(0.5f32.into()),
+                        yaw: Some(0f32.into()),
+                        roll: None,
+                    })),
+                    angular_velocity: partial(Some(1.), Some(2.), None),
+                    ..Default::default()
+                })),
+                boost_amount: Some(50f32.into()),
+            },
+            DesiredCarState {
+                physics: None,
+                boost_amount: Some(100f32.into()),
+            },
+        ],
+        match_info: Some(Box::new(DesiredMatchInfo {
+            world_gravity_z: Some((-650f32).into()),
+            game_speed: None,
+        })),
+        console_commands: Vec::new(),
+    };
+
+    let mirrored = desired_game_state.mirrored();
+    let ball = &mirrored.ball_states[1].physics;
+    let ball_location = ball.location.as_deref().unwrap();
+    assert_eq!(ball_location.x.unwrap().val, -1000.);
+    assert!(ball_location.y.is_none());
+    assert_eq!(ball_location.z.unwrap().val, 500.);
+    assert_eq!(ball.velocity.as_deref().unwrap().y.unwrap().val, 1500.);
+
+    let car = mirrored.car_states[0].physics.as_deref().unwrap();
+    let rotation = car.rotation.as_deref().unwrap();
+    assert_eq!(rotation.pitch.unwrap().val, 0.5);
+    assert_eq!(rotation.yaw.unwrap().val, -PI);
+    assert!(rotation.roll.is_none());
+    assert_eq!(car.location, partial(None, None, Some(17.)));
+    assert!(mirrored.car_states[1].physics.is_none());
+    assert_eq!(mirrored.match_info, desired_game_state.match_info);
+
+    assert_eq!(mirrored.mirrored(), desired_game_state);
+}
+
+#[test]
+fn yaw_round_trip() {
+    for yaw in [-PI, -2., -0.5, 0., 0.5, 2., PI - 0.001] {
+        let rotator = Rotator {
+            pitch: 0.,
+            yaw,
+            roll: 0.,
+        };
+        let mirrored = rotator.mirrored();
+        assert!((-PI..PI).contains(&mirrored.yaw));
+        assert!((mirrored.mirrored().yaw - yaw).abs() < 1e-5, "{yaw}");
+    }
+}
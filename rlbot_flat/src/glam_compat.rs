@@ -50,3 +50,41 @@ impl From<glam::Vec3A> for flat::RenderAnchor {
         }
     }
 }
+
+/// Builds the orientation matrix of a rotator. The columns are the forward,
+/// right, and up vectors, in that order.
+impl From<flat::Rotator> for glam::Mat3 {
+    fn from(value: flat::Rotator) -> Self {
+        let (sp, cp) = value.pitch.sin_cos();
+        let (sy, cy) = value.yaw.sin_cos();
+        let (sr, cr) = value.roll.sin_cos();
+
+        Self::from_cols(
+            glam::Vec3::new(cp * cy, cp * sy, sp),
+            glam::Vec3::new(cy * sp * sr - cr * sy, sy * sp * sr + cr * cy, -cp * sr),
+            glam::Vec3::new(-cr * cy * sp - sr * sy, -cr * sy * sp + sr * cy, cp * cr),
+        )
+    }
+}
+
+impl From<glam::Mat3> for flat::Rotator {
+    fn from(value: glam::Mat3) -> Self {
+        Self {
+            pitch: value.x_axis.z.clamp(-1., 1.).asin(),
+            yaw: value.x_axis.y.atan2(value.x_axis.x),
+            roll: (-value.y_axis.z).atan2(value.z_axis.z),
+        }
+    }
+}
+
+impl From<flat::Rotator> for glam::Quat {
+    fn from(value: flat::Rotator) -> Self {
+        Self::from_mat3(&value.into())
+    }
+}
+
+impl From<glam::Quat> for flat::Rotator {
+    fn from(value: glam::Quat) -> Self {
+        glam::Mat3::from_quat(value).into()
+    }
+}
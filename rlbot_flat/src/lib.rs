@@ -45,6 +45,35 @@ impl From<flat::Physics> for flat::DesiredPhysics {
     }
 }
 
+/// Captures the complete state of a [`flat::GamePacket`], so it can be
+/// restored later.
+impl From<&flat::GamePacket> for flat::DesiredGameState {
+    fn from(value: &flat::GamePacket) -> Self {
+        Self {
+            ball_states: value
+                .balls
+                .iter()
+                .map(|ball| flat::DesiredBallState {
+                    physics: Box::new(ball.physics.into()),
+                })
+                .collect(),
+            car_states: value
+                .players
+                .iter()
+                .map(|player| flat::DesiredCarState {
+                    physics: Some(Box::new(player.physics.into())),
+                    boost_amount: Some(player.boost.into()),
+                })
+                .collect(),
+            match_info: Some(Box::new(flat::DesiredMatchInfo {
+                world_gravity_z: Some(value.match_info.world_gravity_z.into()),
+                game_speed: Some(value.match_info.game_speed.into()),
+            })),
+            ..Default::default()
+        }
+    }
+}
+
 impl From<flat::InterfaceMessage> for flat::InterfacePacket {
     fn from(message: flat::InterfaceMessage) -> Self {
         flat::InterfacePacket { message }